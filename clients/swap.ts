@@ -4,7 +4,6 @@ import { BoosterSwap } from "../target/types/booster_swap";
 import {
   LAMPORTS_PER_SOL_DECIMAL,
   TradeDirection,
-  getMintAuthAddress,
   getObservation,
   logPairBalance,
   setupSwapTest,
@@ -13,6 +12,7 @@ import {
   toBigIntQuantity
 } from "../tests/utils";
 import { getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
 
 async function main() {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -28,7 +28,7 @@ async function main() {
     uri: "https://5vfxc4tr6xoy23qefqbj4qx2adzkzapneebanhcalf7myvn5gzja.arweave.net/7UtxcnH13Y1uBCwCnkL6APKsge0hAgacQFl-zFW9NlI",
     decimals: 9,
   };
-  const [mintAuth] = getMintAuthAddress(program.programId);
+  const mint = Keypair.generate();
   let configAddress, poolAddress, poolState;
 
   async function setup() {
@@ -36,7 +36,7 @@ async function main() {
     const resp = await setupSwapTest(
       program,
       owner,
-      mintAuth,
+      mint,
       metadata,
       {
         config_index: 0,
@@ -0,0 +1,232 @@
+use crate::error::{IndexerError, Result};
+use crate::legacy::{PreDeployPairEventV0, SwapEventV0};
+use crate::records::{Event, PreDeployPairRecord, SwapRecord};
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use booster_swap::states::{LpChangeEvent, PreDeployPairEvent, SwapEvent};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Decodes the events emitted by the booster swap program from the log messages of a transaction.
+/// `Program data` logs are only trusted while the booster swap program is the running invocation,
+/// so other programs can not forge events. An event that can not be decoded is logged and skipped.
+pub fn decode_logs(logs: &[String]) -> Vec<Event> {
+    let program_id = booster_swap::id().to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invocations.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            // `emit!` logs a single field
+            let event = STANDARD
+                .decode(data.split(' ').next().unwrap_or_default())
+                .map_err(|e| IndexerError::MalformedEvent(e.to_string()))
+                .and_then(|data| decode_event(&data));
+            match event {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => eprintln!("skipping event: {}", e),
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let (Some(invoked_program), Some(action)) = (words.next(), words.next()) else {
+                continue;
+            };
+            if action == "invoke" {
+                invocations.push(invoked_program);
+            } else if action == "success" || action == "failed:" {
+                invocations.pop();
+            }
+        }
+    }
+    events
+}
+
+/// Decodes an event from its discriminator and Borsh payload, `None` for other events.
+/// The layouts older versions of the program emitted are decoded too
+pub fn decode_event(data: &[u8]) -> Result<Option<Event>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let (discriminator, payload) = data.split_at(8);
+    let event = if discriminator == SwapEvent::DISCRIMINATOR {
+        let swap = deserialize::<SwapEvent>(payload)
+            .map(SwapRecord::from)
+            .or_else(|_| deserialize::<SwapEventV0>(payload).map(SwapRecord::from))
+            .map_err(|_| unknown_layout("SwapEvent", payload))?;
+        Event::Swap(swap)
+    } else if discriminator == LpChangeEvent::DISCRIMINATOR {
+        Event::LpChange(deserialize::<LpChangeEvent>(payload)?.into())
+    } else if discriminator == PreDeployPairEvent::DISCRIMINATOR {
+        let pre_deploy_pair = deserialize::<PreDeployPairEvent>(payload)
+            .map(PreDeployPairRecord::from)
+            .or_else(|_| {
+                deserialize::<PreDeployPairEventV0>(payload).map(PreDeployPairRecord::from)
+            })
+            .map_err(|_| unknown_layout("PreDeployPairEvent", payload))?;
+        Event::PreDeployPair(pre_deploy_pair)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+fn deserialize<T: AnchorDeserialize>(payload: &[u8]) -> Result<T> {
+    T::try_from_slice(payload).map_err(|e| IndexerError::MalformedEvent(e.to_string()))
+}
+
+fn unknown_layout(event: &str, payload: &[u8]) -> IndexerError {
+    IndexerError::MalformedEvent(format!(
+        "{} of {} bytes matches no known layout",
+        event,
+        payload.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::{LpChangeRecord, SwapRecord};
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::Event as _;
+
+    fn program_data(data: Vec<u8>) -> String {
+        format!("{}{}", PROGRAM_DATA, STANDARD.encode(data))
+    }
+
+    fn swap_event(pool_id: Pubkey) -> SwapEvent {
+        let payer = Pubkey::new_from_array([9u8; 32]);
+        SwapEvent {
+            pool_id,
+            token_0_vault_before: 1_000,
+            token_1_vault_before: 2_000,
+            input_amount: 10,
+            output_amount: 20,
+            base_input: true,
+            trade_direction: 1,
+            trade_fee_rate: 2_500,
+            payer,
+            token_0_vault_after: 980,
+            token_1_vault_after: 2_010,
+            token_0_price_x32: 8_813_272_891,
+            protocol_fee: 1,
+            fund_fee: 1,
+            margin_trade_fee: 0,
+            padding_trade_fee: 0,
+            block_timestamp: 1_717_000_000,
+        }
+    }
+
+    #[test]
+    fn decode_program_events_only() {
+        let program_id = booster_swap::id();
+        let other_program = Pubkey::new_unique();
+        let pool_id = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {} invoke [1]", other_program),
+            program_data(swap_event(Pubkey::new_unique()).data()),
+            format!("Program {} success", other_program),
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: SwapBaseInput".to_string(),
+            format!("Program {} invoke [2]", other_program),
+            program_data(swap_event(Pubkey::new_unique()).data()),
+            format!("Program {} success", other_program),
+            program_data(swap_event(pool_id).data()),
+            program_data(
+                LpChangeEvent {
+                    pool_id,
+                    lp_amount_before: 1,
+                    token_0_vault_before: 2,
+                    token_1_vault_before: 3,
+                    token_0_amount: 4,
+                    token_1_amount: 5,
+                    token_0_transfer_fee: 6,
+                    token_1_transfer_fee: 7,
+                    change_type: 1,
+                }
+                .data(),
+            ),
+            // unknown events are skipped
+            program_data(vec![7u8; 16]),
+            format!("Program {} consumed 5000 of 200000 compute units", program_id),
+            format!("Program {} success", program_id),
+        ];
+
+        assert_eq!(
+            decode_logs(&logs),
+            vec![
+                Event::Swap(SwapRecord::from(swap_event(pool_id))),
+                Event::LpChange(LpChangeRecord {
+                    pool_id,
+                    lp_amount_before: 1,
+                    token_0_vault_before: 2,
+                    token_1_vault_before: 3,
+                    token_0_amount: 4,
+                    token_1_amount: 5,
+                    token_0_transfer_fee: 6,
+                    token_1_transfer_fee: 7,
+                    change_type: 1,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_truncated_event() {
+        let mut data = swap_event(Pubkey::new_unique()).data();
+        data.pop();
+        assert!(decode_event(&data).is_err());
+    }
+
+    #[test]
+    fn decode_older_layouts() {
+        use anchor_lang::AnchorSerialize;
+        let pool_id = Pubkey::new_unique();
+        let event = |discriminator: [u8; 8], payload: Vec<u8>| {
+            decode_event(&[discriminator.to_vec(), payload].concat())
+                .unwrap()
+                .unwrap()
+        };
+        let fields = (pool_id, 1_000u64, 2_000u64, 10u64, 20u64, true, 1u8);
+        let Event::Swap(v0) = event(SwapEvent::DISCRIMINATOR, fields.try_to_vec().unwrap()) else {
+            panic!("not a swap");
+        };
+        assert_eq!(
+            (v0.pool_id, v0.input_amount, v0.output_amount, v0.trade_direction),
+            (pool_id, 10, 20, 1)
+        );
+        assert_eq!((v0.trade_fee_rate, v0.payer), (0, Pubkey::default()));
+
+        let fields = (pool_id, 1u64, 2u64, 3u128, 4u128);
+        let Event::PreDeployPair(pre_deploy_pair) =
+            event(PreDeployPairEvent::DISCRIMINATOR, fields.try_to_vec().unwrap())
+        else {
+            panic!("not a pre deploy pair");
+        };
+        assert_eq!(
+            (pre_deploy_pair.token_1_cumulative, pre_deploy_pair.trade_count),
+            (4, 0)
+        );
+    }
+
+    #[test]
+    fn skip_undecodable_events() {
+        let program_id = booster_swap::id();
+        let pool_id = Pubkey::new_unique();
+        let mut truncated = swap_event(Pubkey::new_unique()).data();
+        truncated.pop();
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            program_data(truncated),
+            format!("{}not base64!", PROGRAM_DATA),
+            program_data(swap_event(pool_id).data()),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(
+            decode_logs(&logs),
+            vec![Event::Swap(SwapRecord::from(swap_event(pool_id)))]
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid transaction json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("malformed event: {0}")]
+    MalformedEvent(String),
+    #[error("malformed transaction: {0}")]
+    MalformedTransaction(String),
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
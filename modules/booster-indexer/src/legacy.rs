@@ -0,0 +1,69 @@
+//! Layouts of the events emitted by older versions of the program, still found in their logs.
+//! The fields they lack are zero in the records.
+use crate::records::{PreDeployPairRecord, SwapRecord};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::prelude::{borsh, AnchorDeserialize};
+
+/// `SwapEvent` before the trade fee rate, the payer, the post-trade state and the fees were added
+#[derive(AnchorDeserialize)]
+pub struct SwapEventV0 {
+    pub pool_id: Pubkey,
+    pub token_0_vault_before: u64,
+    pub token_1_vault_before: u64,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub base_input: bool,
+    pub trade_direction: u8,
+}
+
+impl From<SwapEventV0> for SwapRecord {
+    fn from(event: SwapEventV0) -> Self {
+        SwapRecord {
+            pool_id: event.pool_id,
+            token_0_vault_before: event.token_0_vault_before,
+            token_1_vault_before: event.token_1_vault_before,
+            input_amount: event.input_amount,
+            output_amount: event.output_amount,
+            base_input: event.base_input,
+            trade_direction: event.trade_direction,
+            trade_fee_rate: 0,
+            payer: Pubkey::default(),
+            token_0_vault_after: 0,
+            token_1_vault_after: 0,
+            token_0_price_x32: 0,
+            protocol_fee: 0,
+            fund_fee: 0,
+            margin_trade_fee: 0,
+            padding_trade_fee: 0,
+            block_timestamp: 0,
+        }
+    }
+}
+
+/// `PreDeployPairEvent` before the pool statistics were added
+#[derive(AnchorDeserialize)]
+pub struct PreDeployPairEventV0 {
+    pub pool_id: Pubkey,
+    pub token_0_vault_before: u64,
+    pub token_1_vault_before: u64,
+    pub token_0_cumulative: u128,
+    pub token_1_cumulative: u128,
+}
+
+impl From<PreDeployPairEventV0> for PreDeployPairRecord {
+    fn from(event: PreDeployPairEventV0) -> Self {
+        PreDeployPairRecord {
+            pool_id: event.pool_id,
+            token_0_vault_before: event.token_0_vault_before,
+            token_1_vault_before: event.token_1_vault_before,
+            token_0_cumulative: event.token_0_cumulative,
+            token_1_cumulative: event.token_1_cumulative,
+            buy_volume_token_1: 0,
+            sell_volume_token_1: 0,
+            trade_count: 0,
+            unique_buyer_count: 0,
+            ath_price_x32: 0,
+            graduation_timestamp: 0,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Indexes the events of the booster swap program from transaction logs into SQLite
+pub mod decoder;
+pub mod error;
+pub mod legacy;
+pub mod records;
+pub mod replay;
+pub mod store;
+
+pub use decoder::*;
+pub use error::*;
+pub use records::*;
+pub use replay::*;
+pub use store::*;
@@ -0,0 +1,18 @@
+use booster_indexer::{replay, Store};
+use std::process::exit;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 3 || args[0] != "replay" {
+        eprintln!("usage: booster-indexer replay <sqlite db> <transaction json file or directory>...");
+        exit(2);
+    }
+    let result = Store::open(&args[1]).and_then(|mut store| replay(&args[2..], &mut store));
+    match result {
+        Ok(indexed) => println!("indexed {} new events", indexed),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::Pubkey;
+use booster_swap::states::{LpChangeEvent, PreDeployPairEvent, SwapEvent};
+
+/// Where an event was emitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSource {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// The position of the event among the program events of the transaction
+    pub event_index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapRecord {
+    pub pool_id: Pubkey,
+    pub token_0_vault_before: u64,
+    pub token_1_vault_before: u64,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub base_input: bool,
+    pub trade_direction: u8,
+    pub trade_fee_rate: u64,
+    pub payer: Pubkey,
+    pub token_0_vault_after: u64,
+    pub token_1_vault_after: u64,
+    pub token_0_price_x32: u128,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    pub margin_trade_fee: u64,
+    pub padding_trade_fee: u64,
+    pub block_timestamp: u64,
+}
+
+impl From<SwapEvent> for SwapRecord {
+    fn from(event: SwapEvent) -> Self {
+        SwapRecord {
+            pool_id: event.pool_id,
+            token_0_vault_before: event.token_0_vault_before,
+            token_1_vault_before: event.token_1_vault_before,
+            input_amount: event.input_amount,
+            output_amount: event.output_amount,
+            base_input: event.base_input,
+            trade_direction: event.trade_direction,
+            trade_fee_rate: event.trade_fee_rate,
+            payer: event.payer,
+            token_0_vault_after: event.token_0_vault_after,
+            token_1_vault_after: event.token_1_vault_after,
+            token_0_price_x32: event.token_0_price_x32,
+            protocol_fee: event.protocol_fee,
+            fund_fee: event.fund_fee,
+            margin_trade_fee: event.margin_trade_fee,
+            padding_trade_fee: event.padding_trade_fee,
+            block_timestamp: event.block_timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LpChangeRecord {
+    pub pool_id: Pubkey,
+    pub lp_amount_before: u64,
+    pub token_0_vault_before: u64,
+    pub token_1_vault_before: u64,
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    pub change_type: u8,
+}
+
+impl From<LpChangeEvent> for LpChangeRecord {
+    fn from(event: LpChangeEvent) -> Self {
+        LpChangeRecord {
+            pool_id: event.pool_id,
+            lp_amount_before: event.lp_amount_before,
+            token_0_vault_before: event.token_0_vault_before,
+            token_1_vault_before: event.token_1_vault_before,
+            token_0_amount: event.token_0_amount,
+            token_1_amount: event.token_1_amount,
+            token_0_transfer_fee: event.token_0_transfer_fee,
+            token_1_transfer_fee: event.token_1_transfer_fee,
+            change_type: event.change_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreDeployPairRecord {
+    pub pool_id: Pubkey,
+    pub token_0_vault_before: u64,
+    pub token_1_vault_before: u64,
+    pub token_0_cumulative: u128,
+    pub token_1_cumulative: u128,
+    pub buy_volume_token_1: u64,
+    pub sell_volume_token_1: u64,
+    pub trade_count: u64,
+    pub unique_buyer_count: u64,
+    pub ath_price_x32: u128,
+    pub graduation_timestamp: u64,
+}
+
+impl From<PreDeployPairEvent> for PreDeployPairRecord {
+    fn from(event: PreDeployPairEvent) -> Self {
+        PreDeployPairRecord {
+            pool_id: event.pool_id,
+            token_0_vault_before: event.token_0_vault_before,
+            token_1_vault_before: event.token_1_vault_before,
+            token_0_cumulative: event.token_0_cumulative,
+            token_1_cumulative: event.token_1_cumulative,
+            buy_volume_token_1: event.buy_volume_token_1,
+            sell_volume_token_1: event.sell_volume_token_1,
+            trade_count: event.trade_count,
+            unique_buyer_count: event.unique_buyer_count,
+            ath_price_x32: event.ath_price_x32,
+            graduation_timestamp: event.graduation_timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Swap(SwapRecord),
+    LpChange(LpChangeRecord),
+    PreDeployPair(PreDeployPairRecord),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord {
+    pub source: EventSource,
+    pub event: Event,
+}
@@ -0,0 +1,165 @@
+use crate::decoder::decode_logs;
+use crate::error::{IndexerError, Result};
+use crate::records::{EventRecord, EventSource};
+use crate::store::Store;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Decodes the events of a transaction as returned by the `getTransaction` RPC method
+/// with the `json` encoding, either the JSON-RPC response or its `result`.
+/// A failed transaction has no events.
+pub fn decode_transaction(transaction: &Value) -> Result<Vec<EventRecord>> {
+    let transaction = transaction.get("result").unwrap_or(transaction);
+    let malformed = |field: &str| IndexerError::MalformedTransaction(format!("missing {}", field));
+
+    let meta = transaction.get("meta").ok_or_else(|| malformed("meta"))?;
+    if !meta.get("err").map_or(true, Value::is_null) {
+        return Ok(Vec::new());
+    }
+    let signature = transaction
+        .pointer("/transaction/signatures/0")
+        .and_then(Value::as_str)
+        .ok_or_else(|| malformed("signature"))?;
+    let slot = transaction
+        .get("slot")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| malformed("slot"))?;
+    let block_time = transaction.get("blockTime").and_then(Value::as_i64);
+    let logs = meta
+        .get("logMessages")
+        .and_then(Value::as_array)
+        .ok_or_else(|| malformed("logMessages"))?
+        .iter()
+        .map(|log| log.as_str().map(str::to_string))
+        .collect::<Option<Vec<String>>>()
+        .ok_or_else(|| malformed("logMessages"))?;
+
+    Ok(decode_logs(&logs)
+        .into_iter()
+        .enumerate()
+        .map(|(event_index, event)| EventRecord {
+            source: EventSource {
+                signature: signature.to_string(),
+                slot,
+                block_time,
+                event_index: event_index as u32,
+            },
+            event,
+        })
+        .collect())
+}
+
+/// Reads the transactions of a JSON file holding one transaction or an array of them,
+/// or of every `.json` file of a directory in file name order
+pub fn read_transactions(path: &Path) -> Result<Vec<Value>> {
+    if path.is_dir() {
+        let mut files = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        files.retain(|file| file.extension().map_or(false, |extension| extension == "json"));
+        files.sort();
+        let mut transactions = Vec::new();
+        for file in files {
+            transactions.extend(read_transactions(&file)?);
+        }
+        return Ok(transactions);
+    }
+    match serde_json::from_str(&fs::read_to_string(path)?)? {
+        Value::Array(transactions) => Ok(transactions),
+        transaction => Ok(vec![transaction]),
+    }
+}
+
+/// Indexes the transactions found at `paths`, returning the number of new events
+pub fn replay<P: AsRef<Path>>(paths: &[P], store: &mut Store) -> Result<usize> {
+    let mut records = Vec::new();
+    for path in paths {
+        for transaction in read_transactions(path.as_ref())? {
+            records.extend(decode_transaction(&transaction)?);
+        }
+    }
+    store.insert_all(&records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::{Event, PreDeployPairRecord};
+    use anchor_lang::prelude::Pubkey;
+    use std::str::FromStr;
+
+    fn fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+    }
+
+    #[test]
+    fn replay_fixtures() {
+        let mut store = Store::open_in_memory().unwrap();
+        assert_eq!(replay(&[fixtures()], &mut store).unwrap(), 4);
+        assert_eq!(store.count("swaps").unwrap(), 3);
+        assert_eq!(store.count("lp_changes").unwrap(), 0);
+        assert_eq!(store.count("pre_deploy_pairs").unwrap(), 1);
+
+        // replaying indexes nothing twice
+        assert_eq!(replay(&[fixtures()], &mut store).unwrap(), 0);
+        assert_eq!(store.count("swaps").unwrap(), 3);
+
+        let pool_id = Pubkey::from_str("7Nsk9zJQwvnRWfjpkFcyJ8DydaLmbaby24tp9T5gFTK").unwrap();
+        let swaps = store.swaps(&pool_id).unwrap();
+        assert_eq!(swaps.len(), 3);
+        let (source, buy) = &swaps[0];
+        assert_eq!(source.slot, 1_200);
+        assert_eq!(source.block_time, Some(1_717_000_000));
+        assert_eq!(buy.trade_direction, 1);
+        assert_eq!(buy.input_amount, 1_000_000_000);
+        assert_eq!(buy.output_amount, 32_894_736_842_105);
+        assert_eq!(buy.trade_fee_rate, 10_000);
+        assert_eq!(
+            buy.payer,
+            Pubkey::from_str("C1nmLcgDfFoAnMxpwQ9kdKJwvwiGauJhdyESSCKNSnnk").unwrap()
+        );
+        assert_eq!(
+            (buy.token_0_vault_after, buy.token_1_vault_after),
+            (767_105_263_157_895, 25_000_890_880)
+        );
+        assert_eq!(buy.token_0_price_x32, 139_978);
+        assert_eq!((buy.protocol_fee, buy.fund_fee), (2_500_000, 500_000));
+        assert_eq!((buy.margin_trade_fee, buy.padding_trade_fee), (10_000_000, 0));
+        assert_eq!(buy.block_timestamp, 1_717_000_000);
+        // the batch emits its legs in order
+        assert_eq!(swaps[1].0.signature, swaps[2].0.signature);
+        assert_eq!((swaps[1].0.event_index, swaps[2].0.event_index), (0, 1));
+        assert_eq!(swaps[1].1.trade_direction, 0);
+        assert_eq!(swaps[2].1.trade_direction, 1);
+    }
+
+    #[test]
+    fn decode_pre_deploy_pair_fixture() {
+        let transactions = read_transactions(&fixtures().join("pre_deploy_pair.json")).unwrap();
+        let records = decode_transaction(&transactions[0]).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].event,
+            Event::PreDeployPair(PreDeployPairRecord {
+                pool_id: Pubkey::from_str("7Nsk9zJQwvnRWfjpkFcyJ8DydaLmbaby24tp9T5gFTK").unwrap(),
+                token_0_vault_before: 206_900_000_000_000,
+                token_1_vault_before: 109_000_000_000,
+                token_0_cumulative: 1_234_567_890_123_456_789_012,
+                token_1_cumulative: 98_765_432_109_876_543_210,
+                buy_volume_token_1: 120_000_000_000,
+                sell_volume_token_1: 11_000_000_000,
+                trade_count: 42,
+                unique_buyer_count: 17,
+                ath_price_x32: 150_000,
+                graduation_timestamp: 1_717_000_600,
+            })
+        );
+    }
+
+    #[test]
+    fn failed_transaction_has_no_events() {
+        let transactions = read_transactions(&fixtures().join("failed_swap.json")).unwrap();
+        assert!(decode_transaction(&transactions[0]).unwrap().is_empty());
+    }
+}
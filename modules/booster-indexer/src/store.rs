@@ -0,0 +1,249 @@
+use crate::error::Result;
+use crate::records::{Event, EventRecord, EventSource, SwapRecord};
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, Row};
+use std::path::Path;
+use std::str::FromStr;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS swaps (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    pool_id TEXT NOT NULL,
+    token_0_vault_before INTEGER NOT NULL,
+    token_1_vault_before INTEGER NOT NULL,
+    input_amount INTEGER NOT NULL,
+    output_amount INTEGER NOT NULL,
+    base_input INTEGER NOT NULL,
+    trade_direction INTEGER NOT NULL,
+    trade_fee_rate INTEGER NOT NULL,
+    payer TEXT NOT NULL,
+    token_0_vault_after INTEGER NOT NULL,
+    token_1_vault_after INTEGER NOT NULL,
+    -- u128 do not fit SQLite integers
+    token_0_price_x32 TEXT NOT NULL,
+    protocol_fee INTEGER NOT NULL,
+    fund_fee INTEGER NOT NULL,
+    margin_trade_fee INTEGER NOT NULL,
+    padding_trade_fee INTEGER NOT NULL,
+    block_timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS swaps_pool_id ON swaps (pool_id, slot);
+CREATE TABLE IF NOT EXISTS lp_changes (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    pool_id TEXT NOT NULL,
+    lp_amount_before INTEGER NOT NULL,
+    token_0_vault_before INTEGER NOT NULL,
+    token_1_vault_before INTEGER NOT NULL,
+    token_0_amount INTEGER NOT NULL,
+    token_1_amount INTEGER NOT NULL,
+    token_0_transfer_fee INTEGER NOT NULL,
+    token_1_transfer_fee INTEGER NOT NULL,
+    change_type INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS pre_deploy_pairs (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    pool_id TEXT NOT NULL,
+    token_0_vault_before INTEGER NOT NULL,
+    token_1_vault_before INTEGER NOT NULL,
+    -- u128 do not fit SQLite integers
+    token_0_cumulative TEXT NOT NULL,
+    token_1_cumulative TEXT NOT NULL,
+    buy_volume_token_1 INTEGER NOT NULL,
+    sell_volume_token_1 INTEGER NOT NULL,
+    trade_count INTEGER NOT NULL,
+    unique_buyer_count INTEGER NOT NULL,
+    ath_price_x32 TEXT NOT NULL,
+    graduation_timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+";
+
+/// SQLite storage of the indexed events, one table per event.
+/// Events are keyed by transaction signature and event index, so replaying is idempotent.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn })
+    }
+
+    /// Inserts the records in a single SQLite transaction, returning how many were not indexed yet
+    pub fn insert_all(&mut self, records: &[EventRecord]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        for record in records {
+            inserted += insert(&tx, record)?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// The swaps of a pool by slot, each transaction's in the order it emitted them.
+    /// `getTransaction` does not give the position of a transaction in its block,
+    /// so the transactions of one slot are ordered by signature, not by execution
+    pub fn swaps(&self, pool_id: &Pubkey) -> Result<Vec<(EventSource, SwapRecord)>> {
+        let mut statement = self.conn.prepare(
+            "SELECT signature, event_index, slot, block_time, pool_id, token_0_vault_before,
+                token_1_vault_before, input_amount, output_amount, base_input, trade_direction,
+                trade_fee_rate, payer, token_0_vault_after, token_1_vault_after,
+                token_0_price_x32, protocol_fee, fund_fee, margin_trade_fee, padding_trade_fee,
+                block_timestamp
+            FROM swaps WHERE pool_id = ?1 ORDER BY slot, signature, event_index",
+        )?;
+        let swaps = statement
+            .query_map([pool_id.to_string()], |row| {
+                Ok((
+                    event_source(row)?,
+                    SwapRecord {
+                        pool_id: pubkey(row, 4)?,
+                        token_0_vault_before: row.get(5)?,
+                        token_1_vault_before: row.get(6)?,
+                        input_amount: row.get(7)?,
+                        output_amount: row.get(8)?,
+                        base_input: row.get(9)?,
+                        trade_direction: row.get(10)?,
+                        trade_fee_rate: row.get(11)?,
+                        payer: pubkey(row, 12)?,
+                        token_0_vault_after: row.get(13)?,
+                        token_1_vault_after: row.get(14)?,
+                        token_0_price_x32: u128_text(row, 15)?,
+                        protocol_fee: row.get(16)?,
+                        fund_fee: row.get(17)?,
+                        margin_trade_fee: row.get(18)?,
+                        padding_trade_fee: row.get(19)?,
+                        block_timestamp: row.get(20)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(swaps)
+    }
+
+    /// The number of rows of a table
+    pub fn count(&self, table: &str) -> Result<u64> {
+        let count: i64 =
+            self.conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })?;
+        Ok(count as u64)
+    }
+}
+
+fn insert(conn: &Connection, record: &EventRecord) -> Result<usize> {
+    let source = &record.source;
+    let inserted = match &record.event {
+        Event::Swap(swap) => conn.execute(
+            "INSERT OR IGNORE INTO swaps VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+            params![
+                source.signature,
+                source.event_index,
+                source.slot,
+                source.block_time,
+                swap.pool_id.to_string(),
+                swap.token_0_vault_before,
+                swap.token_1_vault_before,
+                swap.input_amount,
+                swap.output_amount,
+                swap.base_input,
+                swap.trade_direction,
+                swap.trade_fee_rate,
+                swap.payer.to_string(),
+                swap.token_0_vault_after,
+                swap.token_1_vault_after,
+                swap.token_0_price_x32.to_string(),
+                swap.protocol_fee,
+                swap.fund_fee,
+                swap.margin_trade_fee,
+                swap.padding_trade_fee,
+                swap.block_timestamp,
+            ],
+        )?,
+        Event::LpChange(lp_change) => conn.execute(
+            "INSERT OR IGNORE INTO lp_changes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                source.signature,
+                source.event_index,
+                source.slot,
+                source.block_time,
+                lp_change.pool_id.to_string(),
+                lp_change.lp_amount_before,
+                lp_change.token_0_vault_before,
+                lp_change.token_1_vault_before,
+                lp_change.token_0_amount,
+                lp_change.token_1_amount,
+                lp_change.token_0_transfer_fee,
+                lp_change.token_1_transfer_fee,
+                lp_change.change_type,
+            ],
+        )?,
+        Event::PreDeployPair(pre_deploy_pair) => conn.execute(
+            "INSERT OR IGNORE INTO pre_deploy_pairs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                ?11, ?12, ?13, ?14, ?15)",
+            params![
+                source.signature,
+                source.event_index,
+                source.slot,
+                source.block_time,
+                pre_deploy_pair.pool_id.to_string(),
+                pre_deploy_pair.token_0_vault_before,
+                pre_deploy_pair.token_1_vault_before,
+                pre_deploy_pair.token_0_cumulative.to_string(),
+                pre_deploy_pair.token_1_cumulative.to_string(),
+                pre_deploy_pair.buy_volume_token_1,
+                pre_deploy_pair.sell_volume_token_1,
+                pre_deploy_pair.trade_count,
+                pre_deploy_pair.unique_buyer_count,
+                pre_deploy_pair.ath_price_x32.to_string(),
+                pre_deploy_pair.graduation_timestamp,
+            ],
+        )?,
+    };
+    Ok(inserted)
+}
+
+fn event_source(row: &Row) -> rusqlite::Result<EventSource> {
+    Ok(EventSource {
+        signature: row.get(0)?,
+        event_index: row.get(1)?,
+        slot: row.get(2)?,
+        block_time: row.get(3)?,
+    })
+}
+
+fn pubkey(row: &Row, index: usize) -> rusqlite::Result<Pubkey> {
+    let value: String = row.get(index)?;
+    Pubkey::from_str(&value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn u128_text(row: &Row, index: usize) -> rusqlite::Result<u128> {
+    let value: String = row.get(index)?;
+    value.parse().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
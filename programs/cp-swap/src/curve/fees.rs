@@ -1,6 +1,8 @@
 //! All fee information, to be used for validation currently
 
 pub const FEE_RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
+/// Number of times the sniper premium is halved over the protection window when decaying exponentially
+pub const SNIPER_FEE_HALVINGS: u64 = 10;
 
 pub struct Fees {}
 
@@ -50,6 +52,34 @@ impl Fees {
         )
     }
 
+    /// Calculate the fee rate during the sniper-protection window.
+    /// The rate starts at `sniper_fee_rate` when the pool opens and decays to `base_fee_rate`
+    /// once `elapsed` reaches `duration`, either linearly or by halving the premium
+    /// `SNIPER_FEE_HALVINGS` times (interpolated between halvings).
+    pub fn sniper_fee_rate(
+        base_fee_rate: u64,
+        sniper_fee_rate: u64,
+        duration: u64,
+        elapsed: u64,
+        exponential: bool,
+    ) -> u64 {
+        if duration == 0 || elapsed >= duration || sniper_fee_rate <= base_fee_rate {
+            return base_fee_rate;
+        }
+        let premium = u128::from(sniper_fee_rate - base_fee_rate);
+        let duration = u128::from(duration);
+        let elapsed = u128::from(elapsed);
+        let remaining_premium = if exponential {
+            let steps = elapsed * u128::from(SNIPER_FEE_HALVINGS);
+            let halvings = steps / duration;
+            let step_premium = premium >> halvings;
+            step_premium - (step_premium - step_premium / 2) * (steps % duration) / duration
+        } else {
+            premium * (duration - elapsed) / duration
+        };
+        base_fee_rate + remaining_premium as u64
+    }
+
     pub fn calculate_pre_fee_amount(post_fee_amount: u128, trade_fee_rate: u64) -> Option<u128> {
         if trade_fee_rate == 0 {
             Some(post_fee_amount)
@@ -64,4 +94,82 @@ impl Fees {
                 .checked_div(denominator)
         }
     }
+
+    /// The largest input whose trading fee, charged on top of it, fits in `amount_with_fee`
+    pub fn calculate_amount_before_fee(amount_with_fee: u128, trade_fee_rate: u64) -> Option<u128> {
+        floor_div(
+            amount_with_fee,
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE).checked_add(u128::from(trade_fee_rate))?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniper_fee_rate_linear_decay() {
+        // 50% at open, decays to 1% over 100 seconds
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 0, false), 500_000);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 25, false), 377_500);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 50, false), 255_000);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 99, false), 14_900);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 100, false), 10_000);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 100, 1_000, false), 10_000);
+    }
+
+    #[test]
+    fn sniper_fee_rate_exponential_decay() {
+        // the premium is halved every duration / SNIPER_FEE_HALVINGS seconds
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 0, true), 512_000);
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 5, true), 384_000);
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 10, true), 256_000);
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 20, true), 128_000);
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 90, true), 1_000);
+        assert_eq!(Fees::sniper_fee_rate(0, 512_000, 100, 100, true), 0);
+
+        let mut last = u64::MAX;
+        for elapsed in 0..=100 {
+            let rate = Fees::sniper_fee_rate(2_500, 512_000, 100, elapsed, true);
+            assert!(rate <= last);
+            assert!(rate >= 2_500);
+            last = rate;
+        }
+    }
+
+    #[test]
+    fn sniper_fee_rate_disabled() {
+        assert_eq!(Fees::sniper_fee_rate(10_000, 500_000, 0, 0, false), 10_000);
+        assert_eq!(Fees::sniper_fee_rate(10_000, 5_000, 100, 0, true), 10_000);
+    }
+
+    #[test]
+    fn amount_before_fee_spends_the_most_that_fits() {
+        for trade_fee_rate in [0, 1, 2_500, 10_000, 999_999] {
+            let denominator = FEE_RATE_DENOMINATOR_VALUE + trade_fee_rate;
+            // around the multiples of the denominator, where the floor switches
+            for multiple in [0u128, 1, 2, 1_000, 1_000_000_000] {
+                for offset in [-2i128, -1, 0, 1, 2] {
+                    let amount_with_fee = u128::from(denominator) * multiple;
+                    let Some(amount_with_fee) = amount_with_fee.checked_add_signed(offset) else {
+                        continue;
+                    };
+                    let amount_in =
+                        Fees::calculate_amount_before_fee(amount_with_fee, trade_fee_rate).unwrap();
+                    let spent = |amount: u128| {
+                        amount + Fees::trading_fee(amount, trade_fee_rate).unwrap()
+                    };
+                    assert!(spent(amount_in) <= amount_with_fee);
+                    assert!(spent(amount_in + 1) > amount_with_fee);
+                }
+            }
+        }
+        // 1 SOL at a 1% fee
+        assert_eq!(
+            Fees::calculate_amount_before_fee(1_000_000_000, 10_000),
+            Some(990_099_009)
+        );
+    }
 }
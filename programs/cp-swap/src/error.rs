@@ -33,4 +33,30 @@ pub enum ErrorCode {
     InvalidVault,
     #[msg("Marketcap is too low")]
     InvalidMarketCap,
+    #[msg("Exceeds the max buy amount per wallet in the opening window")]
+    ExceededMaxBuy,
+    #[msg("Invalid presale proof")]
+    InvalidPresaleProof,
+    #[msg("Exceeds the presale allocation")]
+    ExceededPresaleAllocation,
+    #[msg("Swap deadline exceeded")]
+    DeadlineExceeded,
+    #[msg("Not enough oracle observations")]
+    InsufficientObservations,
+    #[msg("Invalid fee split recipients")]
+    InvalidFeeSplit,
+    #[msg("Buyback ran too recently")]
+    BuybackTooFrequent,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Invalid token metadata")]
+    InvalidMetadata,
+    #[msg("Unknown pool state version")]
+    InvalidPoolStateVersion,
+    #[msg("Oracle ring too large to create, grow it with grow_observation")]
+    ObservationNumTooLarge,
+    #[msg("The candle account of the pool is missing")]
+    CandleStateMissing,
 }
@@ -9,7 +9,7 @@ use anchor_spl::token_interface::TokenAccount;
 #[derive(Accounts)]
 pub struct CollectFundFee<'info> {
     /// Only admin or fund_owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
+    #[account(constraint = (owner.key() == amm_config.load()?.fund_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -27,7 +27,7 @@ pub struct CollectFundFee<'info> {
 
     /// Amm config account stores fund_owner
     #[account(address = pool_state.load()?.amm_config)]
-    pub amm_config: Account<'info, AmmConfig>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -53,7 +53,7 @@ pub struct CollectFundFee<'info> {
     #[account(mut)]
     pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// CHECK: The address that receives the collected token_1 fund fees
+    /// CHECK: The address that receives the collected token_1 fund fees, paid in lamports
     #[account(mut, address = owner.key())]
     pub recipient_token_1_account: UncheckedAccount<'info>,
 
@@ -74,7 +74,12 @@ pub fn collect_fund_fee(
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     {
         amount_0 = amount_0_requested.min(pool_state.fund_fees_token_0);
-        amount_1 = amount_1_requested.min(pool_state.fund_fees_token_1);
+        amount_1 = native_vault_collectable_amount(
+            amount_1_requested,
+            pool_state.fund_fees_token_1,
+            ctx.accounts.token_1_vault.lamports(),
+            Rent::get()?.minimum_balance(0),
+        );
 
         pool_state.fund_fees_token_0 = pool_state.fund_fees_token_0.checked_sub(amount_0).unwrap();
         pool_state.fund_fees_token_1 = pool_state.fund_fees_token_1.checked_sub(amount_1).unwrap();
@@ -8,7 +8,7 @@ use anchor_spl::token_interface::{Mint, TokenAccount};
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
     /// Only admin or owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
+    #[account(constraint = (owner.key() == amm_config.load()?.protocol_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -26,7 +26,7 @@ pub struct CollectProtocolFee<'info> {
 
     /// Amm config account stores owner
     #[account(address = pool_state.load()?.amm_config)]
-    pub amm_config: Account<'info, AmmConfig>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -52,9 +52,9 @@ pub struct CollectProtocolFee<'info> {
     #[account(mut)]
     pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The address that receives the collected token_1 protocol fees
+    /// CHECK: The address that receives the collected token_1 protocol fees, paid in lamports
     #[account(mut)]
-    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient_token_1_account: UncheckedAccount<'info>,
 
     /// The SPL program to perform token transfers
     pub token_program: Program<'info, Token>,
@@ -73,7 +73,12 @@ pub fn collect_protocol_fee(
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     {
         amount_0 = amount_0_requested.min(pool_state.protocol_fees_token_0);
-        amount_1 = amount_1_requested.min(pool_state.protocol_fees_token_1);
+        amount_1 = native_vault_collectable_amount(
+            amount_1_requested,
+            pool_state.protocol_fees_token_1,
+            ctx.accounts.token_1_vault.lamports(),
+            Rent::get()?.minimum_balance(0),
+        );
 
         pool_state.protocol_fees_token_0 = pool_state
             .protocol_fees_token_0
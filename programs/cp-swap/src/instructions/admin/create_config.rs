@@ -1,7 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
-use std::ops::DerefMut;
 
 #[derive(Accounts)]
 #[instruction(index: u16)]
@@ -24,7 +23,7 @@ pub struct CreateAmmConfig<'info> {
         payer = owner,
         space = AmmConfig::LEN
     )]
-    pub amm_config: Account<'info, AmmConfig>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     pub system_program: Program<'info, System>,
 }
@@ -38,7 +37,7 @@ pub fn create_amm_config(
     fund_fee_rate: u64,
     create_pool_fee: u64,
 ) -> Result<()> {
-    let amm_config = ctx.accounts.amm_config.deref_mut();
+    let amm_config = &mut ctx.accounts.amm_config.load_init()?;
     amm_config.protocol_owner = ctx.accounts.owner.key();
     amm_config.bump = ctx.bumps.amm_config;
     amm_config.disable_create_pool = false;
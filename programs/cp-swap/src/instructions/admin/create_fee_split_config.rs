@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateFeeSplitConfig<'info> {
+    /// Only admin can set up a fee split
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// The amm config whose pools' fees are split
+    pub amm_config: AccountLoader<'info, AmmConfig>,
+
+    /// Initialize the fee split of the amm config
+    #[account(
+        init,
+        seeds = [
+            FEE_SPLIT_CONFIG_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FeeSplitConfig::LEN
+    )]
+    pub fee_split_config: Account<'info, FeeSplitConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_fee_split_config(
+    ctx: Context<CreateFeeSplitConfig>,
+    recipients: Vec<FeeSplitRecipient>,
+) -> Result<()> {
+    let fee_split_config = &mut ctx.accounts.fee_split_config;
+    fee_split_config.bump = ctx.bumps.fee_split_config;
+    fee_split_config.amm_config = ctx.accounts.amm_config.key();
+    fee_split_config.set_recipients(&recipients)
+}
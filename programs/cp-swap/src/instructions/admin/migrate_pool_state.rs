@@ -0,0 +1,37 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigratePoolState<'info> {
+    /// Only the admin can migrate, and pays the rent if the account grows
+    #[account(
+        mut,
+        address = crate::admin::id()
+    )]
+    pub authority: Signer<'info>,
+
+    /// The pool to upgrade to the current layout. Accounts of version 0 were sized
+    /// 613 bytes, larger than `PoolState::LEN`, and are never shrunk
+    #[account(
+        mut,
+        realloc = PoolState::LEN.max(pool_state.as_ref().data_len()),
+        realloc::payer = authority,
+        realloc::zero = true,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let from_version = pool_state.version;
+    if pool_state.migrate()? {
+        msg!(
+            "pool state migrated from version {} to {}",
+            from_version,
+            POOL_STATE_VERSION
+        );
+    }
+    Ok(())
+}
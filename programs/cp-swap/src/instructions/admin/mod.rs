@@ -12,3 +12,12 @@ pub use collect_protocol_fee::*;
 
 pub mod collect_fund_fee;
 pub use collect_fund_fee::*;
+
+pub mod create_fee_split_config;
+pub use create_fee_split_config::*;
+
+pub mod update_fee_split_config;
+pub use update_fee_split_config::*;
+
+pub mod migrate_pool_state;
+pub use migrate_pool_state::*;
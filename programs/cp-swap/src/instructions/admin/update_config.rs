@@ -11,11 +11,11 @@ pub struct UpdateAmmConfig<'info> {
 
     /// Amm config account to be changed
     #[account(mut)]
-    pub amm_config: Account<'info, AmmConfig>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 }
 
 pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
-    let amm_config = &mut ctx.accounts.amm_config;
+    let amm_config = &mut ctx.accounts.amm_config.load_mut()?;
     let match_param = Some(param);
     match match_param {
         Some(0) => update_trade_zero_to_one_fee_rate(amm_config, value),
@@ -32,35 +32,57 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -
         }
         Some(6) => amm_config.create_pool_fee = value,
         Some(7) => amm_config.disable_create_pool = if value == 0 { false } else { true },
+        Some(8) => update_sniper_fee_rate(amm_config, value),
+        Some(9) => amm_config.sniper_duration = value,
+        Some(10) => {
+            let decay_mode = u8::try_from(value).map_err(|_| ErrorCode::InvalidInput)?;
+            require!(
+                SniperFeeDecay::from_u8(decay_mode).is_some(),
+                ErrorCode::InvalidInput
+            );
+            amm_config.sniper_decay_mode = decay_mode;
+        }
+        Some(11) => update_max_buy_rate(amm_config, value),
+        Some(12) => amm_config.max_buy_duration = value,
         _ => return err!(ErrorCode::InvalidInput),
     }
 
     Ok(())
 }
 
-fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u64) {
+fn update_protocol_fee_rate(amm_config: &mut AmmConfig, protocol_fee_rate: u64) {
     assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
     assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
     amm_config.protocol_fee_rate = protocol_fee_rate;
 }
 
-fn update_trade_zero_to_one_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) {
+fn update_trade_zero_to_one_fee_rate(amm_config: &mut AmmConfig, trade_fee_rate: u64) {
     assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
     amm_config.trade_from_zero_to_one_fee_rate = trade_fee_rate;
 }
 
-fn update_trade_one_to_zero_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) {
+fn update_trade_one_to_zero_fee_rate(amm_config: &mut AmmConfig, trade_fee_rate: u64) {
     assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
     amm_config.trade_from_one_to_zero_fee_rate = trade_fee_rate;
 }
 
-fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u64) {
+fn update_sniper_fee_rate(amm_config: &mut AmmConfig, sniper_fee_rate: u64) {
+    assert!(sniper_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.sniper_fee_rate = sniper_fee_rate;
+}
+
+fn update_max_buy_rate(amm_config: &mut AmmConfig, max_buy_rate: u64) {
+    assert!(max_buy_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.max_buy_rate = max_buy_rate;
+}
+
+fn update_fund_fee_rate(amm_config: &mut AmmConfig, fund_fee_rate: u64) {
     assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
     assert!(fund_fee_rate + amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
-fn set_new_protocol_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) -> Result<()> {
+fn set_new_protocol_owner(amm_config: &mut AmmConfig, new_owner: Pubkey) -> Result<()> {
     require_keys_neq!(new_owner, Pubkey::default());
     #[cfg(feature = "enable-log")]
     msg!(
@@ -72,7 +94,7 @@ fn set_new_protocol_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey
     Ok(())
 }
 
-fn set_new_fund_owner(amm_config: &mut Account<AmmConfig>, new_fund_owner: Pubkey) -> Result<()> {
+fn set_new_fund_owner(amm_config: &mut AmmConfig, new_fund_owner: Pubkey) -> Result<()> {
     require_keys_neq!(new_fund_owner, Pubkey::default());
     #[cfg(feature = "enable-log")]
     msg!(
@@ -0,0 +1,21 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeSplitConfig<'info> {
+    /// Only admin can change a fee split
+    #[account(address = crate::admin::id() @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The fee split to be changed
+    #[account(mut)]
+    pub fee_split_config: Account<'info, FeeSplitConfig>,
+}
+
+pub fn update_fee_split_config(
+    ctx: Context<UpdateFeeSplitConfig>,
+    recipients: Vec<FeeSplitRecipient>,
+) -> Result<()> {
+    ctx.accounts.fee_split_config.set_recipients(&recipients)
+}
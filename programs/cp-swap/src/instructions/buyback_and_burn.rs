@@ -0,0 +1,187 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::{math::to_decimals, token::*};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct BuybackAndBurn<'info> {
+    /// The pool creator, or the protocol owner or admin to whom the protocol fees belong
+    #[account(constraint = (owner.key() == pool_state.load()?.pool_creator || owner.key() == amm_config.load()?.protocol_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool whose token_0 is bought back
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the protocol owner
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: AccountLoader<'info, AmmConfig>,
+
+    /// The vault token account for token 0, the bought token_0 is burned from it
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The vault token account for token 1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: UncheckedAccount<'info>,
+
+    /// The mint of token_0
+    #[account(
+        mut,
+        address = pool_state.load()?.token_0_mint
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// The candles of the pool, required once `create_candle_state` created them
+    #[account(
+        mut,
+        seeds = [
+            CANDLE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub candle_state: Option<AccountLoader<'info, CandleState>>,
+}
+
+/// Buys token_0 on the bonding curve with the token_1 protocol fees and burns it.
+/// The token_1 never leaves the vault, it moves from the fees to the curve reserve.
+/// Buying back a graduated token on its Raydium pool is out of scope: graduation does not
+/// migrate the liquidity to Raydium in this program, and the pool state is closed at graduation.
+pub fn buyback_and_burn(
+    ctx: Context<BuybackAndBurn>,
+    amount_in_requested: u64,
+    minimum_burn_amount: u64,
+) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+        || block_timestamp <= pool_state.open_time
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    // the creator decides whether the fees of its pool are spent on buybacks
+    require_eq!(pool_state.buyback_enabled, 1, ErrorCode::NotApproved);
+    if ctx.accounts.candle_state.is_none() {
+        require_keys_eq!(
+            pool_state.candle_key,
+            Pubkey::default(),
+            ErrorCode::CandleStateMissing
+        );
+    }
+    require!(
+        pool_state.is_buyback_allowed(block_timestamp),
+        ErrorCode::BuybackTooFrequent
+    );
+
+    let amount_in = amount_in_requested.min(pool_state.protocol_fees_token_1);
+    require_gt!(amount_in, 0, ErrorCode::InvalidInput);
+
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?;
+    let (virtual_token_0_amount, virtual_token_1_amount) = pool_state.virtual_reserves(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.lamports(),
+        frozen_amount,
+    );
+    // the pool trades with itself, no trade fee is taken
+    let result = CurveCalculator::swap_base_input(
+        u128::from(amount_in),
+        u128::from(virtual_token_1_amount),
+        u128::from(virtual_token_0_amount),
+        0,
+        0,
+        0,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+    let burn_amount = u64::try_from(result.destination_amount_swapped).unwrap();
+    require_gt!(burn_amount, 0, ErrorCode::ZeroTradingTokens);
+    require_gte!(
+        burn_amount,
+        minimum_burn_amount,
+        ErrorCode::ExceededSlippage
+    );
+
+    // the oracle accumulates the prices since the latest trade before the buyback moves them
+    let (token_0_price_before_x32, token_1_price_before_x32) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.lamports(),
+        frozen_amount,
+    );
+    let (mut observation_state, mut observation_extension) =
+        ObservationState::load_mut_with_extension(&ctx.accounts.observation_state)?;
+    observation_state.update(
+        &mut observation_extension,
+        block_timestamp,
+        token_0_price_before_x32,
+        token_1_price_before_x32,
+    );
+
+    pool_state.protocol_fees_token_1 -= amount_in;
+    pool_state.last_buyback_time = block_timestamp;
+
+    token_burn(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        burn_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+
+    ctx.accounts.token_0_vault.reload()?;
+    let (token_0_price_x32, _) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.lamports(),
+        frozen_amount,
+    );
+
+    // the buyback trades like a buy of the pool itself, it never makes a new buyer
+    pool_state.record_swap(true, amount_in, token_0_price_x32, false);
+
+    if let Some(candle_state) = &ctx.accounts.candle_state {
+        candle_state.load_mut()?.update(
+            block_timestamp,
+            token_0_price_before_x32,
+            token_0_price_x32,
+            burn_amount,
+            amount_in,
+        );
+    }
+
+    emit!(BuybackEvent {
+        pool_id,
+        amount_in,
+        burn_amount,
+        token_0_price_x32,
+        block_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    /// The wallet that locked the tokens
+    pub beneficiary: Signer<'info>,
+
+    /// CHECK: vesting vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The lock to claim from
+    #[account(
+        mut,
+        has_one = beneficiary @ ErrorCode::InvalidOwner,
+    )]
+    pub vesting_state: Box<Account<'info, VestingState>>,
+
+    /// The escrow holding the locked token_0
+    #[account(
+        mut,
+        address = vesting_state.vault
+    )]
+    pub vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The beneficiary token account receiving the claimed token_0
+    #[account(mut)]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0
+    #[account(
+        address = vesting_vault.mint
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let vesting_state = &mut ctx.accounts.vesting_state;
+    let amount = vesting_state.claimable_amount(block_timestamp);
+    require_gt!(amount, 0, ErrorCode::InvalidInput);
+    vesting_state.claimed_amount = vesting_state.claimed_amount.checked_add(amount).unwrap();
+
+    transfer_token(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vesting_vault.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.token_0_mint.decimals,
+        false,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    emit!(VestingClaimedEvent {
+        pool_id: vesting_state.pool_id,
+        beneficiary: vesting_state.beneficiary,
+        amount,
+        claimed_amount: vesting_state.claimed_amount,
+    });
+
+    Ok(())
+}
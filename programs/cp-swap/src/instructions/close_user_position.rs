@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program;
+
+#[derive(Accounts)]
+pub struct CloseUserPosition<'info> {
+    /// The wallet owning the position, receives its rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The pool of the position, closed by `pre_deploy_pair` once the pool graduated
+    #[account(address = user_position.pool_id)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// The amm config of the pool, to read the max buy window
+    pub amm_config: AccountLoader<'info, AmmConfig>,
+
+    /// The position to close
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        close = owner,
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+}
+
+pub fn close_user_position(ctx: Context<CloseUserPosition>) -> Result<()> {
+    let pool_state_info = ctx.accounts.pool_state.to_account_info();
+    // a graduated pool has no caps left
+    if pool_state_info.owner != ctx.program_id || pool_state_info.data_is_empty() {
+        return Ok(());
+    }
+    let data = pool_state_info.try_borrow_data()?;
+    require!(
+        data.len() >= PoolState::LEN && data[..8] == PoolState::discriminator(),
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    let pool_state: &PoolState = bytemuck::from_bytes(&data[8..PoolState::LEN]);
+    require_keys_eq!(
+        ctx.accounts.amm_config.key(),
+        pool_state.amm_config,
+        anchor_lang::error::ErrorCode::ConstraintAddress
+    );
+    // the position backs the presale allocation and the max buy, both must be over
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    require!(
+        block_timestamp > pool_state.open_time
+            && ctx
+                .accounts
+                .amm_config
+                .load()?
+                .max_buy_amount_at(
+                    block_timestamp - pool_state.open_time,
+                    pool_state.mint_0_decimals.into(),
+                )?
+                .is_none(),
+        ErrorCode::NotApproved
+    );
+    Ok(())
+}
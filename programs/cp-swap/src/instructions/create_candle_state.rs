@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateCandleState<'info> {
+    /// Address paying to create the candle account, the pool creator or the admin only
+    #[account(
+        mut,
+        constraint = (payer.key() == pool_state.load()?.pool_creator || payer.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub payer: Signer<'info>,
+
+    /// The pool to record candles for
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// an account to store the candles of the pool
+    #[account(
+        init,
+        seeds = [
+            CANDLE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = CandleState::LEN
+    )]
+    pub candle_state: AccountLoader<'info, CandleState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_candle_state(ctx: Context<CreateCandleState>) -> Result<()> {
+    let mut candle_state = ctx.accounts.candle_state.load_init()?;
+    candle_state.pool_id = ctx.accounts.pool_state.key();
+    // the swaps passing the candle account are checked against it
+    ctx.accounts.pool_state.load_mut()?.candle_key = ctx.accounts.candle_state.key();
+    Ok(())
+}
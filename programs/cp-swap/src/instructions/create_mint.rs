@@ -1,7 +1,9 @@
+use crate::error::ErrorCode;
+use crate::utils::token::*;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+        create_metadata_accounts_v3, mpl_token_metadata::types::{Creator, DataV2}, CreateMetadataAccountsV3,
         Metadata as Metaplex,
     },
     token::{Mint, Token},
@@ -16,18 +18,24 @@ pub struct CreateMint<'info> {
     #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
 
+    /// The new mint, a keypair signed by the creator so its address can be ground off-chain
     #[account(
         init,
-        seeds = [
-            crate::CREATE_MINT_SEED.as_bytes(),
-        ],
-        bump,
         payer = creator,
         mint::decimals = params.decimals,
-        mint::authority = mint,
+        mint::authority = authority,
     )]
     pub mint: Account<'info, Mint>,
 
+    /// CHECK: mint and metadata authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub creator: Signer<'info>,
 
@@ -41,7 +49,14 @@ pub struct CreateMint<'info> {
 }
 
 pub fn create_mint(ctx: Context<CreateMint>, metadata: crate::MintParams) -> Result<()> {
-    let seeds = &[crate::CREATE_MINT_SEED.as_bytes(), &[ctx.bumps.mint]];
+    validate_token_metadata(&metadata.name, &metadata.symbol, &metadata.uri)?;
+    require_gte!(
+        MAX_MINT_DECIMALS,
+        metadata.decimals,
+        ErrorCode::InvalidMetadata
+    );
+
+    let seeds = &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
     let signer = [&seeds[..]];
 
     let token_data: DataV2 = DataV2 {
@@ -49,7 +64,12 @@ pub fn create_mint(ctx: Context<CreateMint>, metadata: crate::MintParams) -> Res
         symbol: metadata.symbol,
         uri: metadata.uri,
         seller_fee_basis_points: 0,
-        creators: None,
+        // the unverified creator binds the mint to its creator, only it can initialize the pool
+        creators: Some(vec![Creator {
+            address: ctx.accounts.creator.key(),
+            verified: false,
+            share: 100,
+        }]),
         collection: None,
         uses: None,
     };
@@ -58,17 +78,18 @@ pub fn create_mint(ctx: Context<CreateMint>, metadata: crate::MintParams) -> Res
         ctx.accounts.token_metadata_program.to_account_info(),
         CreateMetadataAccountsV3 {
             payer: ctx.accounts.creator.to_account_info(),
-            update_authority: ctx.accounts.mint.to_account_info(),
+            update_authority: ctx.accounts.authority.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
             metadata: ctx.accounts.metadata.to_account_info(),
-            mint_authority: ctx.accounts.mint.to_account_info(),
+            mint_authority: ctx.accounts.authority.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
             rent: ctx.accounts.rent.to_account_info(),
         },
         &signer,
     );
 
-    create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
+    // mutable until pre_deploy_pair freezes it, the authority PDA signs as update authority
+    create_metadata_accounts_v3(metadata_ctx, token_data, true, true, None)?;
 
     msg!("Token mint created successfully.");
 
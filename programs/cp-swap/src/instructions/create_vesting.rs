@@ -0,0 +1,114 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    /// Only the pool creator can lock tokens of the pool
+    #[account(
+        mut,
+        constraint = pool_state.load()?.pool_creator == pool_creator.key() @ ErrorCode::InvalidOwner
+    )]
+    pub pool_creator: Signer<'info>,
+
+    /// CHECK: vesting vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool of the locked token_0
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The lock of the pool creator
+    #[account(
+        init,
+        seeds = [
+            VESTING_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            pool_creator.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = VestingState::LEN
+    )]
+    pub vesting_state: Box<Account<'info, VestingState>>,
+
+    /// The escrow holding the locked token_0
+    #[account(
+        init,
+        seeds = [
+            VESTING_VAULT_SEED.as_bytes(),
+            vesting_state.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        token::mint = token_0_mint,
+        token::authority = authority,
+    )]
+    pub vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool creator token account to lock token_0 from
+    #[account(mut)]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0
+    #[account(
+        address = pool_state.load()?.token_0_mint
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_vesting(
+    ctx: Context<CreateVesting>,
+    amount: u64,
+    schedule: VestingSchedule,
+) -> Result<()> {
+    schedule.validate(solana_program::clock::Clock::get()?.unix_timestamp as u64)?;
+    let pool_id = ctx.accounts.pool_state.key();
+    let beneficiary = ctx.accounts.pool_creator.key();
+    ctx.accounts.vesting_state.initialize(
+        ctx.bumps.vesting_state,
+        pool_id,
+        beneficiary,
+        ctx.accounts.vesting_vault.key(),
+        amount,
+        schedule,
+    )?;
+
+    transfer_token(
+        ctx.accounts.pool_creator.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vesting_vault.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.token_0_mint.decimals,
+        true,
+        &[],
+    )?;
+
+    emit!(VestingCreatedEvent {
+        pool_id,
+        beneficiary,
+        amount,
+        start_time: schedule.start_time,
+        cliff_time: schedule.cliff_time,
+        end_time: schedule.end_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// Number of accounts each recipient passes through `remaining_accounts`:
+/// its token_0 account, then its wallet receiving token_1 lamports
+pub const FEE_SPLIT_RECIPIENT_ACCOUNTS_LEN: usize = 2;
+
+#[derive(Accounts)]
+pub struct DistributeFees<'info> {
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated protocol and fund fee amounts
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The fee split of the pool's amm config
+    #[account(
+        seeds = [
+            FEE_SPLIT_CONFIG_SEED.as_bytes(),
+            pool_state.load()?.amm_config.as_ref(),
+        ],
+        bump = fee_split_config.bump,
+    )]
+    pub fee_split_config: Account<'info, FeeSplitConfig>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: UncheckedAccount<'info>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays the protocol and fund fees accrued to the pool to the recipients of the fee split,
+/// each recipient passing its accounts through `remaining_accounts` in the split's order.
+/// token_1 is capped so the vault stays rent exempt, the rest stays accrued.
+pub fn distribute_fees<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DistributeFees<'info>>,
+) -> Result<()> {
+    let fee_split_config = &ctx.accounts.fee_split_config;
+    let recipients = fee_split_config.recipients();
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        recipients.len() * FEE_SPLIT_RECIPIENT_ACCOUNTS_LEN,
+        ErrorCode::InvalidFeeSplit
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let amount_0 = pool_state
+        .protocol_fees_token_0
+        .checked_add(pool_state.fund_fees_token_0)
+        .unwrap();
+    let amount_1 = native_vault_collectable_amount(
+        u64::MAX,
+        pool_state
+            .protocol_fees_token_1
+            .checked_add(pool_state.fund_fees_token_1)
+            .unwrap(),
+        ctx.accounts.token_1_vault.lamports(),
+        Rent::get()?.minimum_balance(0),
+    );
+
+    pool_state.protocol_fees_token_0 = 0;
+    pool_state.fund_fees_token_0 = 0;
+    // the protocol fees are paid out first when the vault can not pay all token_1 fees
+    let protocol_amount_1 = amount_1.min(pool_state.protocol_fees_token_1);
+    pool_state.protocol_fees_token_1 -= protocol_amount_1;
+    pool_state.fund_fees_token_1 = pool_state
+        .fund_fees_token_1
+        .checked_sub(amount_1 - protocol_amount_1)
+        .unwrap();
+
+    let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+    for (((recipient, recipient_accounts), share_0), share_1) in recipients
+        .iter()
+        .zip(remaining_accounts.chunks(FEE_SPLIT_RECIPIENT_ACCOUNTS_LEN))
+        .zip(fee_split_config.split(amount_0))
+        .zip(fee_split_config.split(amount_1))
+    {
+        let recipient_token_0_account =
+            InterfaceAccount::<TokenAccount>::try_from(&recipient_accounts[0])?;
+        require_keys_eq!(
+            recipient_token_0_account.owner,
+            recipient.wallet,
+            ErrorCode::InvalidFeeSplit
+        );
+        require_keys_eq!(
+            recipient_token_0_account.mint,
+            ctx.accounts.vault_0_mint.key(),
+            ErrorCode::InvalidFeeSplit
+        );
+        require_keys_eq!(
+            recipient_accounts[1].key(),
+            recipient.wallet,
+            ErrorCode::InvalidFeeSplit
+        );
+
+        transfer_token(
+            ctx.accounts.authority.to_account_info(),
+            recipient_accounts[0].clone(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            ctx.accounts.vault_0_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            share_0,
+            ctx.accounts.vault_0_mint.decimals,
+            false,
+            &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        )?;
+        transfer_native_token(
+            ctx.accounts.token_1_vault.to_account_info(),
+            recipient_accounts[1].clone(),
+            share_1,
+            false,
+            ctx.accounts.system_program.to_account_info(),
+            &[&[
+                POOL_VAULT_SEED.as_bytes(),
+                ctx.accounts.pool_state.key().as_ref(),
+                ctx.accounts.system_program.key().as_ref(),
+                &[pool_state.vault_1_bump][..],
+            ][..]],
+        )?;
+    }
+
+    Ok(())
+}
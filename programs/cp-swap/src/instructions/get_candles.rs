@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// The most candles `get_candles` returns, the return data is limited to 1KiB
+pub const GET_CANDLES_MAX: u8 = 10;
+
+#[derive(Accounts)]
+pub struct GetCandles<'info> {
+    /// The pool to read
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The candles of the pool
+    #[account(address = pool_state.load()?.candle_key)]
+    pub candle_state: AccountLoader<'info, CandleState>,
+}
+
+/// A candle of the token_0 price, as `Candle` is stored
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct CandleData {
+    /// The start timestamp of the bucket
+    pub start_time: u64,
+    /// token_0 price in token_1, Q32.32
+    pub open_price_x32: u128,
+    pub high_price_x32: u128,
+    pub low_price_x32: u128,
+    pub close_price_x32: u128,
+    /// token_0 bought and sold during the bucket
+    pub volume_token_0: u64,
+    /// token_1 paid and received during the bucket
+    pub volume_token_1: u64,
+}
+
+pub fn get_candles(ctx: Context<GetCandles>, resolution: u8, count: u8) -> Result<Vec<CandleData>> {
+    require!(
+        count > 0 && count <= GET_CANDLES_MAX,
+        ErrorCode::InvalidInput
+    );
+    let resolution = CandleResolution::ALL
+        .into_iter()
+        .find(|candle_resolution| *candle_resolution as u8 == resolution)
+        .ok_or(ErrorCode::InvalidInput)?;
+    let candles = ctx.accounts.candle_state.load()?.get_candles(resolution);
+    let skip = candles.len().saturating_sub(usize::from(count));
+    Ok(candles[skip..]
+        .iter()
+        .map(|candle| CandleData {
+            start_time: candle.start_time,
+            open_price_x32: candle.open_price_x32,
+            high_price_x32: candle.high_price_x32,
+            low_price_x32: candle.low_price_x32,
+            close_price_x32: candle.close_price_x32,
+            volume_token_0: candle.volume_token_0,
+            volume_token_1: candle.volume_token_1,
+        })
+        .collect())
+}
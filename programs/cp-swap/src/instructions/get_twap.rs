@@ -0,0 +1,56 @@
+use crate::states::*;
+use crate::utils::math::to_decimals;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    /// The pool to read, its reserves give the price since the latest trade
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault token account for token 0
+    #[account(address = pool_state.load()?.token_0_vault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The vault token account for token 1
+    #[account(address = pool_state.load()?.token_1_vault)]
+    pub token_1_vault: UncheckedAccount<'info>,
+
+    /// The mint of token_0
+    #[account(address = pool_state.load()?.token_0_mint)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The oracle of the pool
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// Time weighted average prices of a pool, Q32.32
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct TwapPrice {
+    /// token_1 per token_0
+    pub token_0_price_x32: u128,
+    /// token_0 per token_1
+    pub token_1_price_x32: u128,
+}
+
+pub fn get_twap(ctx: Context<GetTwap>, window_secs: u64) -> Result<TwapPrice> {
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?;
+    let current_prices = ctx.accounts.pool_state.load()?.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.lamports(),
+        frozen_amount,
+    );
+    let (observation_state, observation_extension) =
+        ObservationState::load_with_extension(&ctx.accounts.observation_state)?;
+    let (token_0_price_x32, token_1_price_x32) = observation_state.twap(
+        &observation_extension,
+        oracle::block_timestamp(),
+        window_secs,
+        current_prices,
+    )?;
+    Ok(TwapPrice {
+        token_0_price_x32,
+        token_1_price_x32,
+    })
+}
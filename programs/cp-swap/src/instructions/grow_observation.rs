@@ -0,0 +1,27 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(observation_num_next: u16)]
+pub struct GrowObservation<'info> {
+    /// Pays the rent of the grown account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The oracle to grow
+    #[account(
+        mut,
+        realloc = ObservationState::space(observation_num_next).max(observation_state.as_ref().data_len()),
+        realloc::payer = payer,
+        realloc::zero = true,
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn grow_observation(ctx: Context<GrowObservation>, observation_num_next: u16) -> Result<()> {
+    let (mut observation_state, observation_extension) =
+        ObservationState::load_mut_with_extension(&ctx.accounts.observation_state)?;
+    observation_state.grow(&observation_extension, observation_num_next)
+}
@@ -6,19 +6,23 @@ use crate::utils::*;
 use anchor_lang::{accounts::interface_account::InterfaceAccount, prelude::*};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, MintTo, Token},
+    metadata::Metadata as Metaplex,
+    token::{
+        mint_to, set_authority, spl_token::instruction::AuthorityType, MintTo, SetAuthority, Token,
+    },
     token_interface::Mint,
 };
 use spl_memo::solana_program::program_pack::Pack;
 
 #[derive(Accounts)]
+#[instruction(open_time: u64, observation_num: Option<u16>)]
 pub struct Initialize<'info> {
     /// Address paying to create the pool. Can be anyone
     #[account(mut)]
     pub creator: Signer<'info>,
 
     /// Which config the pool belongs to.
-    pub amm_config: Box<Account<'info, AmmConfig>>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     /// CHECK: pool vault and token mint authority
     #[account(
@@ -40,24 +44,36 @@ pub struct Initialize<'info> {
         ],
         bump,
         payer = creator,
-        space = PoolState::LEN
+        space = PoolState::LEN,
+        // init accounts are checked in order, before the observation account is created
+        constraint = observation_num.unwrap_or(OBSERVATION_NUM as u16) <= OBSERVATION_NUM_CREATE_MAX @ ErrorCode::ObservationNumTooLarge,
     )]
     pub pool_state: AccountLoader<'info, PoolState>,
 
     /// Token_0 mint, the key must smaller then token_1 mint.
     #[account(
         mut,
-        seeds = [
-            crate::CREATE_MINT_SEED.as_bytes(),
-        ],
-        bump,
         mint::token_program = token_program,
-        mint::authority = token_0_mint,
+        mint::authority = authority,
         constraint = token_0_mint.supply == 0 @ ErrorCode::IncorrectToken0Mint,
+        constraint = token_0_mint.freeze_authority.is_none() @ ErrorCode::IncorrectToken0Mint,
         // constraint = token_0_mint.key() < token_1_mint.key(),
     )]
     pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// CHECK: The Metaplex metadata of token_0, created by create_mint with the pool creator as its creator
+    #[account(
+        owner = token_metadata_program.key() @ ErrorCode::InvalidMetadata,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
     /// CHECK: Token_0 vault for the pool
     #[account(
         mut,
@@ -98,7 +114,7 @@ pub struct Initialize<'info> {
         ],
         bump,
         payer = creator,
-        space = ObservationState::LEN
+        space = ObservationState::space(observation_num.unwrap_or(OBSERVATION_NUM as u16))
     )]
     pub observation_state: AccountLoader<'info, ObservationState>,
     /// Program to create mint account and mint tokens
@@ -109,16 +125,31 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
     /// Sysvar for program account
     pub rent: Sysvar<'info, Rent>,
+    /// Program of the token_0 metadata
+    pub token_metadata_program: Program<'info, Metaplex>,
 }
 
-pub fn initialize(ctx: Context<Initialize>, open_time: u64) -> Result<()> {
+pub fn initialize(
+    ctx: Context<Initialize>,
+    open_time: u64,
+    observation_num: Option<u16>,
+    observation_update_duration: Option<u16>,
+) -> Result<()> {
     if !is_supported_mint(&ctx.accounts.token_0_mint).unwrap() {
         return err!(ErrorCode::NotSupportMint);
     }
 
-    if ctx.accounts.amm_config.disable_create_pool {
+    let amm_config = ctx.accounts.amm_config.load()?;
+    if amm_config.disable_create_pool {
         return err!(ErrorCode::NotApproved);
     }
+    // any mint under the program authority is accepted, only the wallet that created it
+    // with create_mint can make it a pool
+    require_keys_eq!(
+        mint_creator(&ctx.accounts.metadata, &ctx.accounts.authority.key())?,
+        ctx.accounts.creator.key(),
+        ErrorCode::InvalidOwner
+    );
 
     // due to stack/heap limitations, we have to create redundant new accounts ourselves.
     create_token_account(
@@ -136,26 +167,41 @@ pub fn initialize(ctx: Context<Initialize>, open_time: u64) -> Result<()> {
         ][..]],
     )?;
 
-    let total_supply = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())
-        + to_decimals(AVAILABLE_AMOUNT, ctx.accounts.token_0_mint.decimals.into());
+    let total_supply = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?
+        .checked_add(to_decimals(AVAILABLE_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?)
+        .ok_or(ErrorCode::MathOverflow)?;
     mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
-                authority: ctx.accounts.token_0_mint.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
                 to: ctx.accounts.token_0_vault.to_account_info(),
                 mint: ctx.accounts.token_0_mint.to_account_info(),
             },
-            &[&[
-              crate::CREATE_MINT_SEED.as_bytes(),
-              &[ctx.bumps.token_0_mint],
-            ][..]],
+            &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
         ),
         total_supply,
     )?;
+    // the supply is fixed from now on
+    set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.authority.to_account_info(),
+                account_or_mint: ctx.accounts.token_0_mint.to_account_info(),
+            },
+            &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
 
     let mut observation_state = ctx.accounts.observation_state.load_init()?;
-    observation_state.pool_id = ctx.accounts.pool_state.key();
+    observation_state.initialize(
+        ctx.accounts.pool_state.key(),
+        observation_num.unwrap_or(OBSERVATION_NUM as u16),
+        observation_update_duration.unwrap_or(OBSERVATION_UPDATE_DURATION_DEFAULT),
+    )?;
 
     let pool_state = &mut ctx.accounts.pool_state.load_init()?;
    
@@ -183,11 +229,11 @@ pub fn initialize(ctx: Context<Initialize>, open_time: u64) -> Result<()> {
         .as_u64();
 
     // Charge the fee to create a pool
-    if ctx.accounts.amm_config.create_pool_fee != 0 {
+    if amm_config.create_pool_fee != 0 {
         transfer_native_token(
             ctx.accounts.create_pool_fee.to_account_info(),
             ctx.accounts.creator.to_account_info(),
-            u64::from(ctx.accounts.amm_config.create_pool_fee),
+            amm_config.create_pool_fee,
             true,
             ctx.accounts.system_program.to_account_info(),
             &[],
@@ -1,51 +0,0 @@
-use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
-};
-
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(
-        mut,
-        seeds = [
-            crate::CREATE_MINT_SEED.as_bytes(),
-        ],
-        bump,
-        mint::authority = mint,
-    )]
-    pub mint: Account<'info, Mint>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = payer,
-    )]
-    pub destination: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub rent: Sysvar<'info, Rent>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-}
-
-pub fn mint_tokens(ctx: Context<MintTokens>, quantity: u64) -> Result<()> {
-    let seeds = &[crate::CREATE_MINT_SEED.as_bytes(), &[ctx.bumps.mint]];
-    let signer = [&seeds[..]];
-
-    mint_to(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                authority: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.destination.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-            },
-            &signer,
-        ),
-        quantity,
-    )?;
-
-    Ok(())
-}
@@ -7,6 +7,45 @@ pub mod swap_base_input;
 pub mod swap_base_output;
 pub use swap_base_output::*;
 
+pub mod swap_batch;
+pub use swap_batch::*;
+
+pub mod swap_route;
+pub use swap_route::*;
+
+pub mod close_user_position;
+pub use close_user_position::*;
+
+pub mod set_presale;
+pub use set_presale::*;
+
+pub mod get_twap;
+pub use get_twap::*;
+
+pub mod grow_observation;
+pub use grow_observation::*;
+
+pub mod create_candle_state;
+pub use create_candle_state::*;
+
+pub mod get_candles;
+pub use get_candles::*;
+
+pub mod distribute_fees;
+pub use distribute_fees::*;
+
+pub mod set_buyback;
+pub use set_buyback::*;
+
+pub mod buyback_and_burn;
+pub use buyback_and_burn::*;
+
+pub mod create_vesting;
+pub use create_vesting::*;
+
+pub mod claim_vested;
+pub use claim_vested::*;
+
 // pub mod withdraw;
 // pub use withdraw::*;
 
@@ -16,8 +55,8 @@ pub use admin::*;
 pub mod create_mint;
 pub use create_mint::*;
 
-pub mod mint_tokens;
-pub use mint_tokens::*;
+pub mod update_token_metadata;
+pub use update_token_metadata::*;
 
 pub mod pre_deploy_pair;
 pub use pre_deploy_pair::*;
@@ -4,7 +4,12 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::{token::*, math::{to_decimals}, account::*};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
 use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::accounts::Metadata as MetadataAccount, update_metadata_accounts_v2,
+        Metadata as Metaplex, UpdateMetadataAccountsV2,
+    },
     token::Token,
     token_interface::{Mint, TokenAccount},
 };
@@ -34,7 +39,7 @@ pub struct PreDeployPair<'info> {
 
     /// The factory state to read protocol fees
     #[account(address = pool_state.load()?.amm_config)]
-    pub amm_config: Box<Account<'info, AmmConfig>>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     /// The program account of the pool in which the swap will be performed
     #[account(mut, constraint = pool_state.load()?.pool_creator == payer.key())]
@@ -66,10 +71,23 @@ pub struct PreDeployPair<'info> {
 
     /// The mint of token_0
     #[account(
-        address = pool_state.load()?.token_0_mint 
+        address = pool_state.load()?.token_0_mint,
     )]
     pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// CHECK: The Metaplex metadata of token_0, made immutable
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
     /// The program account for the most recent oracle observation
     #[account(mut, address = pool_state.load()?.observation_key)]
     pub observation_state: AccountLoader<'info, ObservationState>,
@@ -81,11 +99,12 @@ pub struct PreDeployPair<'info> {
     pub token_program: Program<'info, Token>,
 
     pub system_program: Program<'info, System>,
+
+    pub token_metadata_program: Program<'info, Metaplex>,
 }
 
 pub fn pre_deploy_pair(ctx: Context<PreDeployPair>) -> Result<()> {
-    let observation_state = &mut ctx.accounts.observation_state.load_mut()?;
-    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     // @notice must lock the pool before deploy
     if pool_state.get_status_by_bit(PoolStatusBitIndex::Deploy)
     {
@@ -94,7 +113,7 @@ pub fn pre_deploy_pair(ctx: Context<PreDeployPair>) -> Result<()> {
     // lock state to prevent any incoming actions
     pool_state.set_status(1);
 
-    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into());
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?;
     // let available_amount = to_decimals(AVAILABLE_AMOUNT, ctx.accounts.token_0_mint.decimals.into());
     let actual_token_0_amount = ctx.accounts.token_0_vault.amount.checked_sub(frozen_amount).unwrap();
     let actual_token_1_amount = ctx.accounts.token_1_vault.get_lamports().checked_add(BASE_INIT_TOKEN_1_AMOUNT).unwrap();
@@ -163,18 +182,71 @@ pub fn pre_deploy_pair(ctx: Context<PreDeployPair>) -> Result<()> {
     //     &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     // )?;
 
+    // the graduated token metadata can not change anymore,
+    // metadata created immutable before create_mint was fixed is already frozen
+    let metadata = MetadataAccount::from_bytes(&ctx.accounts.metadata.try_borrow_data()?)
+        .map_err(|_| ErrorCode::InvalidMetadata)?;
+    if metadata.is_mutable {
+        // the legacy CREATE_MINT_SEED mint is the update authority of its own metadata
+        let (update_authority, seed, bump) =
+            if metadata.update_authority == ctx.accounts.token_0_mint.key() {
+                let (legacy_mint, bump) = Pubkey::find_program_address(
+                    &[crate::CREATE_MINT_SEED.as_bytes()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(legacy_mint, metadata.update_authority, ErrorCode::InvalidMetadata);
+                (
+                    ctx.accounts.token_0_mint.to_account_info(),
+                    crate::CREATE_MINT_SEED,
+                    bump,
+                )
+            } else {
+                (
+                    ctx.accounts.authority.to_account_info(),
+                    crate::AUTH_SEED,
+                    pool_state.auth_bump,
+                )
+            };
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    update_authority,
+                },
+                &[&[seed.as_bytes(), &[bump]]],
+            ),
+            None,
+            None,
+            None,
+            Some(false),
+        )?;
+    }
+
     // emit event
-    let cumulative = observation_state.get_latest_cumulative();
+    let cumulative = {
+        let (observation_state, observation_extension) =
+            ObservationState::load_with_extension(&ctx.accounts.observation_state)?;
+        observation_state.get_latest_cumulative(&observation_extension)
+    };
     emit!(events::PreDeployPairEvent {
         pool_id: ctx.accounts.pool_state.key(),
         token_0_vault_before: actual_token_0_amount,
         token_1_vault_before: actual_token_1_amount,
         token_0_cumulative: cumulative.0,
         token_1_cumulative: cumulative.1,
+        buy_volume_token_1: pool_state.buy_volume_token_1,
+        sell_volume_token_1: pool_state.sell_volume_token_1,
+        trade_count: pool_state.trade_count,
+        unique_buyer_count: pool_state.unique_buyer_count,
+        ath_price_x32: pool_state.ath_price_x32,
+        graduation_timestamp: solana_program::clock::Clock::get()?.unix_timestamp as u64,
     });
+    let auth_bump = pool_state.auth_bump;
+    // the statistics live on in the event, pool_state is closed below
+    drop(pool_state);
 
-
-    // close `observation_state`, `vault_0`, `vault_1`, `pool_state` account
+    // close `observation_state`, `vault_0` and `pool_state`, `vault_1` was emptied above
     // transfer the rest of balance of all accounts to `create_pool_fee``
     //
     // close token_0_vault token account
@@ -183,19 +255,7 @@ pub fn pre_deploy_pair(ctx: Context<PreDeployPair>) -> Result<()> {
         ctx.accounts.token_0_vault.to_account_info().borrow(),
         ctx.accounts.create_pool_fee.to_account_info().borrow(),
         ctx.accounts.token_program.to_account_info().borrow(),
-        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
-    )?;
-
-    // close token_0_vault account
-    close_account(
-        ctx.accounts.token_0_vault.to_account_info().borrow(),
-        ctx.accounts.create_pool_fee.to_account_info().borrow(),
-    )?;
-
-    // close token_1_vault
-    close_account(
-        ctx.accounts.token_1_vault.to_account_info().borrow(),
-        ctx.accounts.create_pool_fee.to_account_info().borrow(),
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
     )?;
 
     // close pool_state
@@ -0,0 +1,19 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetBuyback<'info> {
+    /// Only the pool creator can opt in to buybacks
+    #[account(constraint = pool_state.load()?.pool_creator == pool_creator.key() @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    /// The pool to opt in or out
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_buyback(ctx: Context<SetBuyback>, enabled: bool) -> Result<()> {
+    ctx.accounts.pool_state.load_mut()?.buyback_enabled = u8::from(enabled);
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+
+#[derive(Accounts)]
+pub struct SetPresale<'info> {
+    /// Only the pool creator can set up a presale
+    #[account(constraint = pool_state.load()?.pool_creator == pool_creator.key() @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    /// The pool to set up a presale for
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_presale(
+    ctx: Context<SetPresale>,
+    presale_open_time: u64,
+    merkle_root: [u8; 32],
+) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // the presale is fixed once it or the public trading started
+    if block_timestamp >= pool_state.open_time
+        || (pool_state.presale_merkle_root != [0u8; 32]
+            && block_timestamp > pool_state.presale_open_time)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    require_gt!(
+        pool_state.open_time,
+        presale_open_time,
+        ErrorCode::InvalidInput
+    );
+
+    pool_state.presale_open_time = presale_open_time;
+    pool_state.presale_merkle_root = merkle_root;
+    Ok(())
+}
@@ -1,8 +1,9 @@
-use crate::curve::calculator::CurveCalculator;
+use crate::curve::calculator::{CurveCalculator, SwapResult};
 use crate::curve::TradeDirection;
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::utils::{token::*, math::to_decimals};
+use crate::PresaleProof;
+use crate::utils::{deadline::check_deadline, merkle::*, token::*, math::to_decimals};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::{
@@ -13,6 +14,7 @@ use anchor_spl::{
 #[derive(Accounts)]
 pub struct Swap<'info> {
     /// The user performing the swap
+    #[account(mut)]
     pub payer: Signer<'info>,
 
      /// CHECK: create pool fee account
@@ -32,8 +34,7 @@ pub struct Swap<'info> {
     pub authority: UncheckedAccount<'info>,
 
     /// The factory state to read protocol fees
-    #[account(address = pool_state.load()?.amm_config)]
-    pub amm_config: Box<Account<'info, AmmConfig>>,
+    pub amm_config: AccountLoader<'info, AmmConfig>,
 
     /// The program account of the pool in which the swap will be performed
     #[account(mut)]
@@ -48,32 +49,84 @@ pub struct Swap<'info> {
     pub token_1_account: UncheckedAccount<'info>,
 
     /// CHECK: The vault token account for token 0
-    #[account(
-        mut,
-        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
-    )]
+    #[account(mut)]
     pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: The vault token account for token 1
-    #[account(
-        mut,
-        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
-    )]
+    #[account(mut)]
     pub token_1_vault: UncheckedAccount<'info>,
 
     /// The mint of token_0
-    #[account(
-        address = pool_state.load()?.token_0_mint 
-    )]
     pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// The program account for the most recent oracle observation
-    #[account(mut, address = pool_state.load()?.observation_key)]
+    #[account(mut)]
     pub observation_state: AccountLoader<'info, ObservationState>,
 
+    /// The token_0 traded by the payer on this pool, created by the first trade at the payer's
+    /// expense (the rent of `UserPosition::LEN` bytes, about 0.002 SOL). It backs the max buy
+    /// and presale caps, `close_user_position` refunds the rent once they are over
+    #[account(
+        init_if_needed,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = UserPosition::LEN
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
     pub token_program: Program<'info, Token>,
 
     pub system_program: Program<'info, System>,
+
+    /// The candles of the pool, required once `create_candle_state` created them
+    #[account(
+        mut,
+        seeds = [
+            CANDLE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub candle_state: Option<AccountLoader<'info, CandleState>>,
+}
+
+impl<'info> Swap<'info> {
+    /// Checks the accounts recorded in the pool against the loaded pool state,
+    /// so the pool is loaded once instead of once per account constraint
+    pub fn validate_pool_accounts(&self, pool_state: &PoolState) -> Result<()> {
+        for (key, expected) in [
+            (self.amm_config.key(), pool_state.amm_config),
+            (self.token_0_vault.key(), pool_state.token_0_vault),
+            (self.token_1_vault.key(), pool_state.token_1_vault),
+            (self.token_0_mint.key(), pool_state.token_0_mint),
+            (self.observation_state.key(), pool_state.observation_key),
+        ] {
+            require_keys_eq!(
+                key,
+                expected,
+                anchor_lang::error::ErrorCode::ConstraintAddress
+            );
+        }
+        // once the pool has candles every swap records them, so none can be left out
+        match &self.candle_state {
+            Some(candle_state) => require_keys_eq!(
+                candle_state.key(),
+                pool_state.candle_key,
+                anchor_lang::error::ErrorCode::ConstraintAddress
+            ),
+            None => require_keys_eq!(
+                pool_state.candle_key,
+                Pubkey::default(),
+                ErrorCode::CandleStateMissing
+            ),
+        }
+        Ok(())
+    }
 }
 
 /// Receive at least
@@ -83,56 +136,74 @@ pub fn swap_base_input(
     trade_direction: u8,
     amount_in: u64,
     minimum_amount_out: u64,
+    presale_proof: Option<PresaleProof>,
+    deadline: Option<u64>,
 ) -> Result<()> {
     let is_zero_for_one = TradeDirection::ZeroForOne.compare_w_u8(trade_direction);
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    check_deadline(deadline, block_timestamp)?;
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    ctx.accounts.validate_pool_accounts(pool_state)?;
+    let amm_config = ctx.accounts.amm_config.load()?;
+    let is_presale = pool_state.is_presale_active(block_timestamp);
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp <= pool_state.open_time
+        || (block_timestamp <= pool_state.open_time && !is_presale)
     {
         return err!(ErrorCode::NotApproved);
     }
+    // only whitelisted buys during the presale
+    let presale_allocation = if is_presale {
+        require!(!is_zero_for_one, ErrorCode::NotApproved);
+        let presale_proof = presale_proof.ok_or(ErrorCode::InvalidPresaleProof)?;
+        require!(
+            verify_merkle_proof(
+                &presale_proof.proof,
+                pool_state.presale_merkle_root,
+                presale_leaf(&ctx.accounts.payer.key(), presale_proof.allocation),
+            ),
+            ErrorCode::InvalidPresaleProof
+        );
+        Some(presale_proof.allocation)
+    } else {
+        None
+    };
     let token_0_vault = ctx.accounts.token_0_vault.clone();
     let token_1_vault = ctx.accounts.token_1_vault.clone();
 
     // Take transfer fees into account for actual amount transferred in
     require_gt!(amount_in, 0);
 
-    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into());
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?;
 
     // Calculate the trade amounts
+    let (virtual_token_0_amount, virtual_token_1_amount) = pool_state.virtual_reserves(
+        token_0_vault.amount,
+        token_1_vault.get_lamports(),
+        frozen_amount,
+    );
+    // the prices since the latest trade, which the oracle accumulates before this one
+    let (token_0_price_before_x32, token_1_price_before_x32) = pool_state.token_price_x32(
+        token_0_vault.amount,
+        token_1_vault.get_lamports(),
+        frozen_amount,
+    );
     let (trade_fee_rate, total_token_0_amount, total_token_1_amount) = if is_zero_for_one {
-        let (total_token_0_amount, total_token_1_amount) = pool_state
-            .vault_amount_without_fee(
-                token_0_vault.amount,
-                token_1_vault.get_lamports(),
-            );
-
         (
-            ctx.accounts.amm_config.trade_from_zero_to_one_fee_rate,
-            total_token_0_amount
-                .checked_sub(frozen_amount)
-                .unwrap(),
-            total_token_1_amount
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
+            amm_config.trade_from_zero_to_one_fee_rate,
+            virtual_token_0_amount,
+            virtual_token_1_amount,
         )
     } else {
-        let (total_token_1_amount, total_token_0_amount) = pool_state
-            .vault_amount_without_fee(
-                token_0_vault.amount,
-                token_1_vault.get_lamports(),
-            );
-
         (
-            ctx.accounts.amm_config.trade_from_one_to_zero_fee_rate,
-            total_token_0_amount
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
-            total_token_1_amount
-                .checked_sub(frozen_amount)
-                .unwrap(),
+            if is_presale {
+                amm_config.trade_from_one_to_zero_fee_rate
+            } else {
+                amm_config
+                    .one_to_zero_fee_rate_at(block_timestamp.saturating_sub(pool_state.open_time))
+            },
+            virtual_token_1_amount,
+            virtual_token_0_amount,
         )
     };
 
@@ -145,8 +216,8 @@ pub fn swap_base_input(
         u128::from(total_token_0_amount),
         u128::from(total_token_1_amount),
         trade_fee_rate,
-        ctx.accounts.amm_config.protocol_fee_rate,
-        ctx.accounts.amm_config.fund_fee_rate,
+        amm_config.protocol_fee_rate,
+        amm_config.fund_fee_rate,
     )
     .ok_or(ErrorCode::ZeroTradingTokens)?;
 
@@ -166,26 +237,6 @@ pub fn swap_base_input(
         u64::try_from(result.source_amount_swapped).unwrap(),
         amount_in
     );
-    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
-    let fund_fee = u64::try_from(result.fund_fee).unwrap();
-    match TradeDirection::to_enum(trade_direction) {
-        TradeDirection::ZeroForOne => {
-            pool_state.protocol_fees_token_0 = pool_state
-                .protocol_fees_token_0
-                .checked_add(protocol_fee)
-                .unwrap();
-            pool_state.fund_fees_token_0 =
-                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
-        }
-        TradeDirection::OneForZero => {
-            pool_state.protocol_fees_token_1 = pool_state
-                .protocol_fees_token_1
-                .checked_add(protocol_fee)
-                .unwrap();
-            pool_state.fund_fees_token_1 =
-                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
-        }
-    };
 
     let (actual_token_0_amount, actual_token_1_amount) = {
         let token_0_transfer_amount = amount_in;
@@ -210,6 +261,71 @@ pub fn swap_base_input(
             (token_1_transfer_amount, token_0_transfer_amount)
         }
     };
+
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.initialize(ctx.bumps.user_position, pool_id, ctx.accounts.payer.key());
+    let is_new_buyer = !is_zero_for_one && user_position.token_0_bought == 0;
+    if is_zero_for_one {
+        user_position.record_sell(actual_token_0_amount);
+    } else {
+        if let Some(max_buy_amount) = amm_config.max_buy_amount_at(
+            block_timestamp.saturating_sub(pool_state.open_time),
+            ctx.accounts.token_0_mint.decimals.into(),
+        )? {
+            // the cap counts every purchase, selling and buying back can not go past it.
+            // Checked before the position changes, so a rejected buy leaves it as it was
+            require_gte!(
+                max_buy_amount,
+                user_position
+                    .token_0_bought
+                    .checked_add(actual_token_0_amount)
+                    .unwrap(),
+                ErrorCode::ExceededMaxBuy
+            );
+        }
+        if let Some(allocation) = presale_allocation {
+            user_position.record_presale_buy(amount_in, allocation)?;
+        }
+        user_position.record_buy(actual_token_0_amount);
+    }
+
+    // update the oracle before the transfers, past the checks only a failed transfer aborts
+    // the swap, which `swap_batch` relies on to skip rejected legs
+    let (mut observation_state, mut observation_extension) =
+        ObservationState::load_mut_with_extension(&ctx.accounts.observation_state)?;
+    let mut candle_state = match &ctx.accounts.candle_state {
+        Some(candle_state) => Some(candle_state.load_mut()?),
+        None => None,
+    };
+    observation_state.update(
+        &mut observation_extension,
+        block_timestamp,
+        token_0_price_before_x32,
+        token_1_price_before_x32,
+    );
+
+    // accrue the fees once every check passed, so a rejected swap leaves the pool state untouched
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    match TradeDirection::to_enum(trade_direction) {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+
     let token_0_authority = if is_zero_for_one {
         ctx.accounts.payer.to_account_info()
     } else {
@@ -269,36 +385,77 @@ pub fn swap_base_input(
     }
     msg!("token_1_vault balance after transfer: {}", ctx.accounts.token_1_vault.get_lamports());
 
-    emit!(SwapEvent {
-        pool_id,
-        token_0_vault_before: total_token_0_amount,
-        token_1_vault_before: total_token_1_amount,
-        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
-        output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
-        base_input: true,
-        trade_direction,
-    });
-
-    // update observation oracle
     ctx.accounts.token_0_vault.reload()?;
-    let (token_0_price_x64, token_1_price_x64) = if is_zero_for_one
-    {
-        pool_state.token_price_x32(
-            ctx.accounts.token_0_vault.amount.checked_sub(frozen_amount).unwrap(),
-            ctx.accounts.token_1_vault.get_lamports().checked_add(BASE_INIT_TOKEN_1_AMOUNT).unwrap(),
-        )
-    } else {
-        pool_state.token_price_x32(
-            ctx.accounts.token_1_vault.get_lamports().checked_add(BASE_INIT_TOKEN_1_AMOUNT).unwrap(),
-            ctx.accounts.token_0_vault.amount.checked_sub(frozen_amount).unwrap(),
-        )
-    };
+    emit!(swap_event(
+        ctx.accounts,
+        pool_state,
+        (virtual_token_0_amount, virtual_token_1_amount),
+        &result,
+        true,
+        trade_direction,
+        trade_fee_rate,
+    )?);
 
-    ctx.accounts.observation_state.load_mut()?.update(
-        oracle::block_timestamp(),
-        token_0_price_x64,
-        token_1_price_x64,
+    let (token_0_price_x32, _) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.get_lamports(),
+        frozen_amount,
     );
+    pool_state.record_swap(
+        !is_zero_for_one,
+        actual_token_1_amount,
+        token_0_price_x32,
+        is_new_buyer,
+    );
+
+    if let Some(candle_state) = candle_state.as_mut() {
+        candle_state.update(
+            block_timestamp,
+            token_0_price_before_x32,
+            token_0_price_x32,
+            actual_token_0_amount,
+            actual_token_1_amount,
+        );
+    }
 
     Ok(())
 }
+
+/// Builds the `SwapEvent` of a swap once the vaults hold their post-trade balances,
+/// shared by `swap_base_input` and `swap_base_output`
+pub(crate) fn swap_event(
+    accounts: &Swap,
+    pool_state: &PoolState,
+    virtual_reserves_before: (u64, u64),
+    result: &SwapResult,
+    base_input: bool,
+    trade_direction: u8,
+    trade_fee_rate: u64,
+) -> Result<SwapEvent> {
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, accounts.token_0_mint.decimals.into())?;
+    let token_0_vault = accounts.token_0_vault.amount;
+    let token_1_vault = accounts.token_1_vault.get_lamports();
+    let (token_0_vault_after, token_1_vault_after) =
+        pool_state.virtual_reserves(token_0_vault, token_1_vault, frozen_amount);
+    let (token_0_price_x32, _) =
+        pool_state.token_price_x32(token_0_vault, token_1_vault, frozen_amount);
+    Ok(SwapEvent {
+        pool_id: accounts.pool_state.key(),
+        token_0_vault_before: virtual_reserves_before.0,
+        token_1_vault_before: virtual_reserves_before.1,
+        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+        output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
+        base_input,
+        trade_direction,
+        trade_fee_rate,
+        payer: accounts.payer.key(),
+        token_0_vault_after,
+        token_1_vault_after,
+        token_0_price_x32,
+        protocol_fee: u64::try_from(result.protocol_fee).unwrap(),
+        fund_fee: u64::try_from(result.fund_fee).unwrap(),
+        margin_trade_fee: u64::try_from(result.margin_trade_fee).unwrap(),
+        padding_trade_fee: u64::try_from(result.padding_trade_fee).unwrap(),
+        block_timestamp: oracle::block_timestamp(),
+    })
+}
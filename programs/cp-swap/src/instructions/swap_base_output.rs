@@ -1,7 +1,8 @@
-use super::swap_base_input::Swap;
+use super::swap_base_input::{swap_event, Swap};
 use crate::curve::{calculator::CurveCalculator, TradeDirection};
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::utils::deadline::check_deadline;
 use crate::utils::math::to_decimals;
 use crate::utils::token::*;
 use anchor_lang::prelude::*;
@@ -14,11 +15,15 @@ pub fn swap_base_output(
     trade_direction: u8,
     max_amount_in: u64,
     amount_out_less_fee: u64,
+    deadline: Option<u64>,
 ) -> Result<()> {
     let is_zero_for_one = TradeDirection::ZeroForOne.compare_w_u8(trade_direction);
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    check_deadline(deadline, block_timestamp)?;
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    ctx.accounts.validate_pool_accounts(pool_state)?;
+    let amm_config = ctx.accounts.amm_config.load()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
         || block_timestamp <= pool_state.open_time
     {
@@ -28,30 +33,31 @@ pub fn swap_base_output(
     let token_0_vault = ctx.accounts.token_0_vault.clone();
     let token_1_vault = ctx.accounts.token_1_vault.clone();
 
-    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into());
+    let frozen_amount = to_decimals(FROZEN_AMOUNT, ctx.accounts.token_0_mint.decimals.into())?;
 
     // Calculate the trade amounts
+    let (virtual_token_0_amount, virtual_token_1_amount) = pool_state.virtual_reserves(
+        token_0_vault.amount,
+        token_1_vault.get_lamports(),
+        frozen_amount,
+    );
+    // the prices since the latest trade, which the oracle accumulates before this one
+    let (token_0_price_before_x32, token_1_price_before_x32) = pool_state.token_price_x32(
+        token_0_vault.amount,
+        token_1_vault.get_lamports(),
+        frozen_amount,
+    );
     let (trade_fee_rate, total_token_0_amount, total_token_1_amount) = if is_zero_for_one {
-        let (total_token_0_amount, total_token_1_amount) =
-            pool_state.vault_amount_without_fee(token_0_vault.amount, token_1_vault.get_lamports());
-
         (
-            ctx.accounts.amm_config.trade_from_zero_to_one_fee_rate,
-            total_token_0_amount.checked_sub(frozen_amount).unwrap(),
-            total_token_1_amount
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
+            amm_config.trade_from_zero_to_one_fee_rate,
+            virtual_token_0_amount,
+            virtual_token_1_amount,
         )
     } else {
-        let (total_token_1_amount, total_token_0_amount) =
-            pool_state.vault_amount_without_fee(token_0_vault.amount, token_1_vault.get_lamports());
-
         (
-            ctx.accounts.amm_config.trade_from_one_to_zero_fee_rate,
-            total_token_0_amount
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
-            total_token_1_amount.checked_sub(frozen_amount).unwrap(),
+            amm_config.one_to_zero_fee_rate_at(block_timestamp.saturating_sub(pool_state.open_time)),
+            virtual_token_1_amount,
+            virtual_token_0_amount,
         )
     };
 
@@ -64,8 +70,8 @@ pub fn swap_base_output(
         u128::from(total_token_0_amount),
         u128::from(total_token_1_amount),
         trade_fee_rate,
-        ctx.accounts.amm_config.protocol_fee_rate,
-        ctx.accounts.amm_config.fund_fee_rate,
+        amm_config.protocol_fee_rate,
+        amm_config.fund_fee_rate,
     )
     .ok_or(ErrorCode::ZeroTradingTokens)?;
 
@@ -83,27 +89,6 @@ pub fn swap_base_output(
     );
     require_gte!(constant_after, constant_before);
 
-    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
-    let fund_fee = u64::try_from(result.fund_fee).unwrap();
-    match TradeDirection::to_enum(trade_direction) {
-        TradeDirection::ZeroForOne => {
-            pool_state.protocol_fees_token_0 = pool_state
-                .protocol_fees_token_0
-                .checked_add(protocol_fee)
-                .unwrap();
-            pool_state.fund_fees_token_0 =
-                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
-        }
-        TradeDirection::OneForZero => {
-            pool_state.protocol_fees_token_1 = pool_state
-                .protocol_fees_token_1
-                .checked_add(protocol_fee)
-                .unwrap();
-            pool_state.fund_fees_token_1 =
-                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
-        }
-    };
-
     // Re-calculate the source amount swapped based on what the curve says
     let (actual_token_0_amount, actual_token_1_amount) = {
         let token_0_transfer_amount = {
@@ -137,6 +122,68 @@ pub fn swap_base_output(
             (token_1_transfer_amount, token_0_transfer_amount)
         }
     };
+
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.initialize(ctx.bumps.user_position, pool_id, ctx.accounts.payer.key());
+    let is_new_buyer = !is_zero_for_one && user_position.token_0_bought == 0;
+    if is_zero_for_one {
+        user_position.record_sell(actual_token_0_amount);
+    } else {
+        if let Some(max_buy_amount) = amm_config.max_buy_amount_at(
+            block_timestamp.saturating_sub(pool_state.open_time),
+            ctx.accounts.token_0_mint.decimals.into(),
+        )? {
+            // the cap counts every purchase, selling and buying back can not go past it.
+            // Checked before the position changes, so a rejected buy leaves it as it was
+            require_gte!(
+                max_buy_amount,
+                user_position
+                    .token_0_bought
+                    .checked_add(actual_token_0_amount)
+                    .unwrap(),
+                ErrorCode::ExceededMaxBuy
+            );
+        }
+        user_position.record_buy(actual_token_0_amount);
+    }
+
+    // update the oracle before the transfers, past the checks only a failed transfer aborts
+    // the swap, which `swap_batch` relies on to skip rejected legs
+    let (mut observation_state, mut observation_extension) =
+        ObservationState::load_mut_with_extension(&ctx.accounts.observation_state)?;
+    let mut candle_state = match &ctx.accounts.candle_state {
+        Some(candle_state) => Some(candle_state.load_mut()?),
+        None => None,
+    };
+    observation_state.update(
+        &mut observation_extension,
+        block_timestamp,
+        token_0_price_before_x32,
+        token_1_price_before_x32,
+    );
+
+    // accrue the fees once every check passed, so a rejected swap leaves the pool state untouched
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    match TradeDirection::to_enum(trade_direction) {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+
     let token_0_authority = if is_zero_for_one {
         ctx.accounts.payer.to_account_info()
     } else {
@@ -194,50 +241,38 @@ pub fn swap_base_output(
         )?;
     }
 
-    emit!(SwapEvent {
-        pool_id,
-        token_0_vault_before: total_token_0_amount,
-        token_1_vault_before: total_token_1_amount,
-        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
-        output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
-        base_input: false,
-        trade_direction,
-    });
-
     ctx.accounts.token_0_vault.reload()?;
-    let (token_0_price_x64, token_1_price_x64) = if is_zero_for_one {
-        pool_state.token_price_x32(
-            ctx.accounts
-                .token_0_vault
-                .amount
-                .checked_sub(frozen_amount)
-                .unwrap(),
-            ctx.accounts
-                .token_1_vault
-                .get_lamports()
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
-        )
-    } else {
-        pool_state.token_price_x32(
-            ctx.accounts
-                .token_1_vault
-                .get_lamports()
-                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
-                .unwrap(),
-            ctx.accounts
-                .token_0_vault
-                .amount
-                .checked_sub(frozen_amount)
-                .unwrap(),
-        )
-    };
+    emit!(swap_event(
+        ctx.accounts,
+        pool_state,
+        (virtual_token_0_amount, virtual_token_1_amount),
+        &result,
+        false,
+        trade_direction,
+        trade_fee_rate,
+    )?);
 
-    ctx.accounts.observation_state.load_mut()?.update(
-        oracle::block_timestamp(),
-        token_0_price_x64,
-        token_1_price_x64,
+    let (token_0_price_x32, _) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.get_lamports(),
+        frozen_amount,
+    );
+    pool_state.record_swap(
+        !is_zero_for_one,
+        actual_token_1_amount,
+        token_0_price_x32,
+        is_new_buyer,
     );
 
+    if let Some(candle_state) = candle_state.as_mut() {
+        candle_state.update(
+            block_timestamp,
+            token_0_price_before_x32,
+            token_0_price_x32,
+            actual_token_0_amount,
+            actual_token_1_amount,
+        );
+    }
+
     Ok(())
 }
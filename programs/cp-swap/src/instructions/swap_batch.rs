@@ -0,0 +1,125 @@
+use super::swap_base_input::*;
+use crate::error::ErrorCode;
+use crate::states::UserPosition;
+use anchor_lang::prelude::*;
+use anchor_lang::Bumps;
+use std::collections::BTreeSet;
+
+/// Number of accounts each leg passes through `remaining_accounts`, laid out as in `Swap`
+pub const SWAP_LEG_ACCOUNTS_LEN: usize = 15;
+/// Index of `user_position` in the accounts of a leg
+const USER_POSITION_INDEX: usize = 11;
+
+/// One swap of a batch, the pool is given by the leg's accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SwapLeg {
+    pub trade_direction: u8,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct SwapBatch<'info> {
+    /// The user performing the swaps, must be the payer of every leg
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+/// How a leg of the batch failed
+enum LegError {
+    /// Rejected by a check before it changed any account, the leg can be skipped
+    Rejected(Error),
+    /// Failed after it changed accounts, the whole batch fails
+    Aborted(Error),
+}
+
+/// Runs `swap_base_input` for each leg on the `Swap` accounts of its pool.
+/// When `skip_failed_legs` is set, a leg rejected by a check is logged and skipped.
+/// `swap_base_input` runs every check before its first write or transfer, so a skipped leg
+/// leaves its pool untouched. The only thing left behind is the empty `user_position`
+/// of a payer trading the pool for the first time, created with its rent before the checks.
+/// Failed transfers, and a leg failing once its position account was created but before
+/// its accounts were loaded, abort the whole transaction.
+pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+    legs: Vec<SwapLeg>,
+    skip_failed_legs: bool,
+    deadline: Option<u64>,
+) -> Result<()> {
+    require!(!legs.is_empty(), ErrorCode::InvalidInput);
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        legs.len() * SWAP_LEG_ACCOUNTS_LEN,
+        ErrorCode::InvalidInput
+    );
+
+    let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+    for (index, (leg, leg_accounts)) in legs
+        .iter()
+        .zip(remaining_accounts.chunks(SWAP_LEG_ACCOUNTS_LEN))
+        .enumerate()
+    {
+        require_keys_eq!(
+            leg_accounts[0].key(),
+            ctx.accounts.payer.key(),
+            ErrorCode::InvalidInput
+        );
+        match swap_leg(ctx.program_id, leg_accounts, leg, deadline) {
+            Ok(()) => {}
+            Err(LegError::Rejected(error)) if skip_failed_legs => {
+                msg!("skip swap leg {}: {}", index, error);
+            }
+            Err(LegError::Rejected(error)) | Err(LegError::Aborted(error)) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+fn swap_leg<'info>(
+    program_id: &Pubkey,
+    leg_accounts: &'info [AccountInfo<'info>],
+    leg: &SwapLeg,
+    deadline: Option<u64>,
+) -> std::result::Result<(), LegError> {
+    if leg.trade_direction > 1 {
+        return Err(LegError::Rejected(error!(ErrorCode::InvalidInput)));
+    }
+    let user_position = &leg_accounts[USER_POSITION_INDEX];
+    let creates_user_position = user_position.owner == &anchor_lang::system_program::ID;
+    let mut accounts_iter = leg_accounts;
+    let mut bumps = <Swap as Bumps>::Bumps::default();
+    let mut reallocs = BTreeSet::new();
+    let mut accounts = match Swap::try_accounts(
+        program_id,
+        &mut accounts_iter,
+        &[],
+        &mut bumps,
+        &mut reallocs,
+    ) {
+        Ok(accounts) => accounts,
+        // the position is created halfway through the accounts, it would be left without data
+        Err(error) if creates_user_position && user_position.owner == program_id => {
+            return Err(LegError::Aborted(error))
+        }
+        Err(error) => return Err(LegError::Rejected(error)),
+    };
+    if let Err(error) = swap_base_input(
+        Context::new(program_id, &mut accounts, &[], bumps),
+        leg.trade_direction,
+        leg.amount_in,
+        leg.minimum_amount_out,
+        None,
+        deadline,
+    ) {
+        // the checks only updated the position in memory, a new one is stored empty
+        if creates_user_position {
+            accounts.user_position.set_inner(UserPosition::default());
+            accounts
+                .user_position
+                .exit(program_id)
+                .map_err(LegError::Aborted)?;
+        }
+        return Err(LegError::Rejected(error));
+    }
+    accounts.exit(program_id).map_err(LegError::Aborted)
+}
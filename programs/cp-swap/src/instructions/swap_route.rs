@@ -0,0 +1,75 @@
+use super::swap_base_input::*;
+use crate::curve::{fees::Fees, TradeDirection};
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    /// The accounts to sell token_0 of the first pool for SOL
+    pub sell: Swap<'info>,
+
+    /// The accounts to buy token_0 of the second pool with the SOL received
+    pub buy: Swap<'info>,
+}
+
+/// Sell `amount_in` token_0 on the first pool and buy token_0 of the second pool with the proceeds
+pub fn swap_route(
+    ctx: Context<SwapRoute>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    deadline: Option<u64>,
+) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.sell.pool_state.key(),
+        ctx.accounts.buy.pool_state.key(),
+        ErrorCode::InvalidInput
+    );
+    require_keys_eq!(
+        ctx.accounts.sell.payer.key(),
+        ctx.accounts.buy.payer.key(),
+        ErrorCode::InvalidInput
+    );
+    let SwapRouteBumps {
+        sell: sell_bumps,
+        buy: buy_bumps,
+    } = ctx.bumps;
+
+    let token_1_before = ctx.accounts.sell.token_1_account.lamports();
+    swap_base_input(
+        Context::new(ctx.program_id, &mut ctx.accounts.sell, &[], sell_bumps),
+        TradeDirection::ZeroForOne as u8,
+        amount_in,
+        0,
+        None,
+        deadline,
+    )?;
+    let token_1_received = ctx
+        .accounts
+        .sell
+        .token_1_account
+        .lamports()
+        .checked_sub(token_1_before)
+        .unwrap();
+
+    // the buy fee is paid on top of the input, leave room for it in the proceeds
+    let buy_fee_rate = {
+        let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+        let open_time = ctx.accounts.buy.pool_state.load()?.open_time;
+        ctx.accounts
+            .buy
+            .amm_config
+            .load()?
+            .one_to_zero_fee_rate_at(block_timestamp.saturating_sub(open_time))
+    };
+    let buy_amount_in =
+        Fees::calculate_amount_before_fee(u128::from(token_1_received), buy_fee_rate).unwrap();
+    swap_base_input(
+        Context::new(ctx.program_id, &mut ctx.accounts.buy, &[], buy_bumps),
+        TradeDirection::OneForZero as u8,
+        u64::try_from(buy_amount_in).unwrap(),
+        minimum_amount_out,
+        None,
+        deadline,
+    )
+}
@@ -0,0 +1,96 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::{accounts::Metadata, types::DataV2},
+        update_metadata_accounts_v2, Metadata as Metaplex, UpdateMetadataAccountsV2,
+    },
+    token_interface::Mint,
+};
+
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    /// Only the pool creator can update the token metadata
+    #[account(constraint = pool_state.load()?.pool_creator == pool_creator.key() @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    /// The pool of the token, must not be locked for graduation
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: token_0 metadata update authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The mint of token_0
+    #[account(
+        address = pool_state.load()?.token_0_mint,
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The Metaplex metadata of token_0
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metaplex>,
+}
+
+pub fn update_token_metadata(
+    ctx: Context<UpdateTokenMetadata>,
+    metadata: crate::TokenMetadataParams,
+) -> Result<()> {
+    // the metadata is frozen once the pool is locked for graduation,
+    // `pre_deploy_pair` then closes the pool state
+    if !ctx
+        .accounts
+        .pool_state
+        .load()?
+        .get_status_by_bit(PoolStatusBitIndex::Deploy)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+
+    validate_token_metadata(&metadata.name, &metadata.symbol, &metadata.uri)?;
+
+    // the update replaces the whole data, keep the creator entry `initialize` checks
+    let current = Metadata::from_bytes(&ctx.accounts.metadata.try_borrow_data()?)
+        .map_err(|_| ErrorCode::InvalidMetadata)?;
+    let token_data = DataV2 {
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        seller_fee_basis_points: current.seller_fee_basis_points,
+        creators: current.creators,
+        collection: None,
+        uses: None,
+    };
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.authority.to_account_info(),
+            },
+            &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+        ),
+        None,
+        Some(token_data),
+        None,
+        None,
+    )
+}
@@ -5,6 +5,7 @@ pub mod states;
 pub mod utils;
 
 use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::states::{FeeSplitRecipient, VestingSchedule};
 use anchor_lang::prelude::*;
 use instructions::*;
 
@@ -51,6 +52,8 @@ pub mod sol_price_feed {
 }
 
 pub const AUTH_SEED: &str = "vault_auth_seed";
+/// Seed of the mint PDA create_mint used before mints were keypairs.
+/// That mint is the update authority of its own metadata, pre_deploy_pair still signs for it.
 pub const CREATE_MINT_SEED: &str = "create_mint";
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -61,6 +64,23 @@ pub struct MintParams {
     pub decimals: u8,
 }
 
+/// The token metadata a pool creator can change before graduation
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TokenMetadataParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// Whitelist entry of the payer in the presale Merkle tree
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PresaleProof {
+    /// The max token_1 the payer can spend in the presale
+    pub allocation: u64,
+    /// The sibling hashes from the payer leaf up to the root
+    pub proof: Vec<[u8; 32]>,
+}
+
 #[program]
 pub mod booster_swap {
     use super::*;
@@ -110,9 +130,19 @@ pub mod booster_swap {
         instructions::create_mint(ctx, metadata)
     }
 
-    /// TEST ONLY
-    pub fn mint_tokens(ctx: Context<MintTokens>, quantity: u64) -> Result<()> {
-        instructions::mint_tokens(ctx, quantity)
+    /// Updates the metadata of the pool token, only while the pool is on the bonding curve.
+    /// Must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `metadata`- The new name, symbol and uri of the token
+    ///
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        metadata: TokenMetadataParams,
+    ) -> Result<()> {
+        instructions::update_token_metadata(ctx, metadata)
     }
 
     /// Updates the owner of the amm config
@@ -126,7 +156,12 @@ pub mod booster_swap {
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9, otherwise will report a error
+    /// * `sniper_fee_rate`- The initial token_1 -> token_0 fee rate after open time, be set when `param` is 8
+    /// * `sniper_duration`- The seconds it takes the sniper fee to decay, be set when `param` is 9
+    /// * `sniper_decay_mode`- 0: linear, 1: exponential, be set when `param` is 10
+    /// * `max_buy_rate`- The max share of the available supply one wallet can hold in the opening window, be set when `param` is 11
+    /// * `max_buy_duration`- The seconds after open time the max buy applies, be set when `param` is 12
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
@@ -143,13 +178,25 @@ pub mod booster_swap {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Upgrade a pool account created with an older layout to the current version,
+    /// growing it first if the layout needs more space. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+        instructions::migrate_pool_state(ctx)
+    }
+
     /// Collect the protocol fee accrued to the pool
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `amount_1_requested` - The maximum amount of token_1 lamports to send, can be 0 to collect fees in only token_0.
+    /// Capped so the token_1 vault stays rent exempt
     ///
     pub fn collect_protocol_fee(
         ctx: Context<CollectProtocolFee>,
@@ -159,13 +206,14 @@ pub mod booster_swap {
         instructions::collect_protocol_fee(ctx, amount_0_requested, amount_1_requested)
     }
 
-    /// Collect the fund fee accrued to the pool
+    /// Collect the fund fee accrued to the pool, the token_1 lamports go to the signing owner
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `amount_1_requested` - The maximum amount of token_1 lamports to send, can be 0 to collect fees in only token_0.
+    /// Capped so the token_1 vault stays rent exempt
     ///
     pub fn collect_fund_fee(
         ctx: Context<CollectFundFee>,
@@ -175,6 +223,104 @@ pub mod booster_swap {
         instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
     }
 
+    /// Creates the fee split of an amm config
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `recipients`- The wallets receiving the fees and their weights in bips, adding up to 10000
+    ///
+    pub fn create_fee_split_config(
+        ctx: Context<CreateFeeSplitConfig>,
+        recipients: Vec<FeeSplitRecipient>,
+    ) -> Result<()> {
+        instructions::create_fee_split_config(ctx, recipients)
+    }
+
+    /// Replaces the recipients of a fee split
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `recipients`- The wallets receiving the fees and their weights in bips, adding up to 10000
+    ///
+    pub fn update_fee_split_config(
+        ctx: Context<UpdateFeeSplitConfig>,
+        recipients: Vec<FeeSplitRecipient>,
+    ) -> Result<()> {
+        instructions::update_fee_split_config(ctx, recipients)
+    }
+
+    /// Pays the protocol and fund fees accrued to the pool to the fee split recipients, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, each recipient passes its token_0 account and wallet through `remaining_accounts`
+    ///
+    pub fn distribute_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DistributeFees<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_fees(ctx)
+    }
+
+    /// Opts the pool in or out of `buyback_and_burn`
+    /// Must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `enabled` - Whether the protocol fees of the pool may be spent on buybacks
+    ///
+    pub fn set_buyback(ctx: Context<SetBuyback>, enabled: bool) -> Result<()> {
+        instructions::set_buyback(ctx, enabled)
+    }
+
+    /// Buys back token_0 on the bonding curve with the token_1 protocol fees and burns it,
+    /// at most once per `BUYBACK_MIN_INTERVAL`. Only pools whose creator opted in with `set_buyback`,
+    /// must be called by the pool creator, the protocol owner or the admin.
+    /// Only pools on the bonding curve, graduated tokens are not bought back on Raydium
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in_requested` - The maximum protocol fees to spend, capped by the accrued fees
+    /// * `minimum_burn_amount` - Minimum amount of token_0 to buy back, prevents excessive slippage
+    ///
+    pub fn buyback_and_burn(
+        ctx: Context<BuybackAndBurn>,
+        amount_in_requested: u64,
+        minimum_burn_amount: u64,
+    ) -> Result<()> {
+        instructions::buyback_and_burn(ctx, amount_in_requested, minimum_burn_amount)
+    }
+
+    /// Locks token_0 of the pool creator in an escrow released on a vesting schedule
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount` - The amount of token_0 to lock
+    /// * `schedule` - The linear release from `start_time` to `end_time`, nothing before `cliff_time`.
+    /// `end_time` must be in the future, so the tokens stay locked for a while
+    ///
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        amount: u64,
+        schedule: VestingSchedule,
+    ) -> Result<()> {
+        instructions::create_vesting(ctx, amount, schedule)
+    }
+
+    /// Claims the vested token_0 of a lock
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        instructions::claim_vested(ctx)
+    }
+
     /// Creates a pool for the given token pair and the initial price
     ///
     /// # Arguments
@@ -183,9 +329,80 @@ pub mod booster_swap {
     /// * `init_amount_0` - the initial amount_0 to deposit
     /// * `init_amount_1` - the initial amount_1 to deposit
     /// * `open_time` - the timestamp allowed for swap
+    /// * `observation_num` - the oracle ring size, defaults to OBSERVATION_NUM. At most OBSERVATION_NUM_CREATE_MAX,
+    ///   as the observation account is created through a CPI limited to 10KiB, `grow_observation` grows it further
+    /// * `observation_update_duration` - the minimum seconds between two oracle observations, defaults to OBSERVATION_UPDATE_DURATION_DEFAULT
+    ///
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        open_time: u64,
+        observation_num: Option<u16>,
+        observation_update_duration: Option<u16>,
+    ) -> Result<()> {
+        instructions::initialize(ctx, open_time, observation_num, observation_update_duration)
+    }
+
+    /// Set up a whitelisted presale that runs until the pool's open time
+    /// Must be called by the pool creator before the presale starts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `presale_open_time` - the timestamp the presale opens
+    /// * `merkle_root` - the root of the (payer, allocation) whitelist, zero to cancel the presale
+    ///
+    pub fn set_presale(
+        ctx: Context<SetPresale>,
+        presale_open_time: u64,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        instructions::set_presale(ctx, presale_open_time, merkle_root)
+    }
+
+    /// Read the time weighted average prices of a pool, returned through the return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `window_secs` - The length of the averaging window, ending now
+    ///
+    pub fn get_twap(ctx: Context<GetTwap>, window_secs: u64) -> Result<TwapPrice> {
+        instructions::get_twap(ctx, window_secs)
+    }
+
+    /// Grow the oracle ring of a pool, the new observations are used once the ring wraps around.
+    /// An account grows by at most 10KiB, i.e. 256 observations, per instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `observation_num_next` - the new ring size
+    ///
+    pub fn grow_observation(ctx: Context<GrowObservation>, observation_num_next: u16) -> Result<()> {
+        instructions::grow_observation(ctx, observation_num_next)
+    }
+
+    /// Create the candle account of a pool, the swaps and buybacks must then pass it and record
+    /// OHLCV candles. Must be called by the pool creator or the admin
+    ///
+    /// # Arguments
     ///
-    pub fn initialize(ctx: Context<Initialize>, open_time: u64) -> Result<()> {
-        instructions::initialize(ctx, open_time)
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_candle_state(ctx: Context<CreateCandleState>) -> Result<()> {
+        instructions::create_candle_state(ctx)
+    }
+
+    /// Read the latest candles of a pool, oldest first, returned through the return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `resolution` - The `CandleResolution` of the candles
+    /// * `count` - How many of the latest candles to return, at most GET_CANDLES_MAX
+    ///
+    pub fn get_candles(ctx: Context<GetCandles>, resolution: u8, count: u8) -> Result<Vec<CandleData>> {
+        instructions::get_candles(ctx, resolution, count)
     }
 
     // /// Withdraw token from Booster CPMM
@@ -212,37 +429,102 @@ pub mod booster_swap {
     // }
 
     /// Swap the tokens in the pool base input amount
+    /// The first trade of a wallet on a pool creates its `UserPosition`, the wallet pays its rent
+    /// and gets it back with `close_user_position` once the max buy window and presale are over
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
     /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
     /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
+    /// * `presale_proof` -  The payer's whitelist entry, required to buy during the presale
+    /// * `deadline` -  The unix timestamp after which the swap fails, none for no expiry
     ///
     pub fn swap_base_input(
         ctx: Context<Swap>,
         trade_direction: u8,
         amount_in: u64,
         minimum_amount_out: u64,
+        presale_proof: Option<PresaleProof>,
+        deadline: Option<u64>,
     ) -> Result<()> {
-        instructions::swap_base_input(ctx, trade_direction, amount_in, minimum_amount_out)
+        instructions::swap_base_input(
+            ctx,
+            trade_direction,
+            amount_in,
+            minimum_amount_out,
+            presale_proof,
+            deadline,
+        )
     }
 
     /// Swap the tokens in the pool base output amount
+    /// The first trade of a wallet on a pool creates its `UserPosition`, the wallet pays its rent
+    /// and gets it back with `close_user_position` once the max buy window and presale are over
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
     /// * `max_amount_in` -  input amount prevents excessive slippage
     /// * `amount_out` -  amount of output token
+    /// * `deadline` -  The unix timestamp after which the swap fails, none for no expiry
     ///
     pub fn swap_base_output(
         ctx: Context<Swap>,
         trade_direction: u8,
         max_amount_in: u64,
         amount_out: u64,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::swap_base_output(ctx, trade_direction, max_amount_in, amount_out, deadline)
+    }
+
+    /// Run `swap_base_input` on several pools in one instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, each leg passes the `Swap` accounts of its pool through `remaining_accounts`
+    /// * `legs` -  The direction, input amount and minimum output amount of each swap
+    /// * `skip_failed_legs` -  Skip the legs that fail their checks instead of failing the whole batch
+    /// * `deadline` -  The unix timestamp after which the legs fail, none for no expiry
+    ///
+    pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+        legs: Vec<SwapLeg>,
+        skip_failed_legs: bool,
+        deadline: Option<u64>,
     ) -> Result<()> {
-        instructions::swap_base_output(ctx, trade_direction, max_amount_in, amount_out)
+        instructions::swap_batch(ctx, legs, skip_failed_legs, deadline)
+    }
+
+    /// Sell token_0 of one pool for SOL and buy token_0 of another pool with the proceeds
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the `Swap` accounts of the pool to sell on then of the pool to buy on
+    /// * `amount_in` -  The amount of token_0 to sell on the first pool
+    /// * `minimum_amount_out` -  Minimum amount of token_0 to receive from the second pool, prevents excessive slippage
+    /// * `deadline` -  The unix timestamp after which the swap fails, none for no expiry
+    ///
+    pub fn swap_route(
+        ctx: Context<SwapRoute>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, amount_in, minimum_amount_out, deadline)
+    }
+
+    /// Close the `UserPosition` of the signer and refund its rent, once the max buy window
+    /// and the presale of its pool are over or the pool graduated.
+    /// A later trade creates the position again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_user_position(ctx: Context<CloseUserPosition>) -> Result<()> {
+        instructions::close_user_position(ctx)
     }
 
     /// Deploy pair
@@ -0,0 +1,224 @@
+/// Candles of the token_0 price, for charts without an off-chain indexer
+///
+use anchor_lang::prelude::*;
+
+/// Seed to derive account address and signature
+pub const CANDLE_SEED: &str = "candle";
+// Number of candles kept per resolution
+pub const CANDLE_NUM: usize = 32;
+// Number of candle resolutions
+pub const CANDLE_RESOLUTION_NUM: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandleResolution {
+    OneMinute = 0,
+    FiveMinutes = 1,
+    OneHour = 2,
+}
+
+impl CandleResolution {
+    pub const ALL: [CandleResolution; CANDLE_RESOLUTION_NUM] = [
+        CandleResolution::OneMinute,
+        CandleResolution::FiveMinutes,
+        CandleResolution::OneHour,
+    ];
+
+    /// The length of a candle in seconds
+    pub fn seconds(&self) -> u64 {
+        match self {
+            CandleResolution::OneMinute => 60,
+            CandleResolution::FiveMinutes => 5 * 60,
+            CandleResolution::OneHour => 60 * 60,
+        }
+    }
+}
+
+/// The element of candles in CandleState
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq)]
+pub struct Candle {
+    /// The start timestamp of the bucket, 0 for an unused candle
+    pub start_time: u64,
+    /// token_0 price in token_1, Q32.32
+    pub open_price_x32: u128,
+    pub high_price_x32: u128,
+    pub low_price_x32: u128,
+    pub close_price_x32: u128,
+    /// token_0 bought and sold during the bucket
+    pub volume_token_0: u64,
+    /// token_1 paid and received during the bucket
+    pub volume_token_1: u64,
+}
+
+impl Candle {
+    pub const LEN: usize = 8 + 16 * 4 + 8 * 2;
+}
+
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CandleState {
+    pub pool_id: Pubkey,
+    /// the most-recently updated index of each resolution's candles
+    pub candle_index: [u16; CANDLE_RESOLUTION_NUM],
+    /// candles rings, indexed by `CandleResolution`
+    pub candles: [[Candle; CANDLE_NUM]; CANDLE_RESOLUTION_NUM],
+    /// padding for feature update
+    pub padding: [u64; 4],
+}
+
+impl Default for CandleState {
+    #[inline]
+    fn default() -> CandleState {
+        CandleState {
+            pool_id: Pubkey::default(),
+            candle_index: [0u16; CANDLE_RESOLUTION_NUM],
+            candles: [[Candle::default(); CANDLE_NUM]; CANDLE_RESOLUTION_NUM],
+            padding: [0u64; 4],
+        }
+    }
+}
+
+impl CandleState {
+    pub const LEN: usize =
+        8 + 32 + 2 * CANDLE_RESOLUTION_NUM + Candle::LEN * CANDLE_NUM * CANDLE_RESOLUTION_NUM + 8 * 4;
+
+    /// Records a trade in the candle of every resolution, opening a new candle
+    /// at the pre-trade price when the trade falls in a new bucket
+    ///
+    /// # Arguments
+    ///
+    /// * `block_timestamp` - The timestamp of the trade
+    /// * `price_before_x32` - The token_0 price before the trade
+    /// * `price_x32` - The token_0 price after the trade
+    /// * `amount_0` - The token_0 traded
+    /// * `amount_1` - The token_1 traded
+    ///
+    pub fn update(
+        &mut self,
+        block_timestamp: u64,
+        price_before_x32: u128,
+        price_x32: u128,
+        amount_0: u64,
+        amount_1: u64,
+    ) {
+        for resolution in CandleResolution::ALL {
+            let start_time = block_timestamp - block_timestamp % resolution.seconds();
+            let ring = &mut self.candles[resolution as usize];
+            let mut candle_index = usize::from(self.candle_index[resolution as usize]);
+            if ring[candle_index].start_time != start_time {
+                // the trade starting the bucket opens it at its pre-trade price
+                if ring[candle_index].start_time != 0 {
+                    candle_index = (candle_index + 1) % CANDLE_NUM;
+                }
+                ring[candle_index] = Candle {
+                    start_time,
+                    open_price_x32: price_before_x32,
+                    high_price_x32: price_before_x32,
+                    low_price_x32: price_before_x32,
+                    close_price_x32: price_before_x32,
+                    volume_token_0: 0,
+                    volume_token_1: 0,
+                };
+                self.candle_index[resolution as usize] = candle_index as u16;
+            }
+            let candle = &mut ring[candle_index];
+            candle.high_price_x32 = price_x32.max(candle.high_price_x32);
+            candle.low_price_x32 = price_x32.min(candle.low_price_x32);
+            candle.close_price_x32 = price_x32;
+            candle.volume_token_0 = candle.volume_token_0.saturating_add(amount_0);
+            candle.volume_token_1 = candle.volume_token_1.saturating_add(amount_1);
+        }
+    }
+
+    /// The recorded candles of a resolution, oldest first
+    pub fn get_candles(&self, resolution: CandleResolution) -> Vec<Candle> {
+        let ring = &self.candles[resolution as usize];
+        let candle_index = usize::from(self.candle_index[resolution as usize]);
+        (1..=CANDLE_NUM)
+            .map(|i| ring[(candle_index + i) % CANDLE_NUM])
+            .filter(|candle| candle.start_time != 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod candle_test {
+    use super::*;
+
+    #[test]
+    fn candle_len() {
+        assert_eq!(CandleState::LEN, 8 + std::mem::size_of::<CandleState>());
+    }
+
+    #[test]
+    fn update_within_and_across_buckets() {
+        let mut candle_state = CandleState::default();
+        candle_state.update(3_600 * 1_000 + 10, 90, 100, 5, 50);
+        candle_state.update(3_600 * 1_000 + 20, 100, 150, 1, 10);
+        candle_state.update(3_600 * 1_000 + 30, 150, 80, 2, 20);
+
+        let candles = candle_state.get_candles(CandleResolution::OneMinute);
+        assert_eq!(
+            candles,
+            vec![Candle {
+                start_time: 3_600 * 1_000,
+                // the first trade opens the candle at its pre-trade price
+                open_price_x32: 90,
+                high_price_x32: 150,
+                low_price_x32: 80,
+                close_price_x32: 80,
+                volume_token_0: 8,
+                volume_token_1: 80,
+            }]
+        );
+
+        // the next minute opens at the pre-trade price, a swap without the candles moved it
+        candle_state.update(3_600 * 1_000 + 70, 85, 120, 3, 30);
+        let candles = candle_state.get_candles(CandleResolution::OneMinute);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(
+            candles[1],
+            Candle {
+                start_time: 3_600 * 1_000 + 60,
+                open_price_x32: 85,
+                high_price_x32: 120,
+                low_price_x32: 85,
+                close_price_x32: 120,
+                volume_token_0: 3,
+                volume_token_1: 30,
+            }
+        );
+
+        // still one candle for the longer resolutions
+        for resolution in [CandleResolution::FiveMinutes, CandleResolution::OneHour] {
+            let candles = candle_state.get_candles(resolution);
+            assert_eq!(candles.len(), 1);
+            assert_eq!({ candles[0].volume_token_0 }, 11);
+            assert_eq!({ candles[0].open_price_x32 }, 90);
+            assert_eq!({ candles[0].high_price_x32 }, 150);
+            assert_eq!({ candles[0].close_price_x32 }, 120);
+        }
+    }
+
+    #[test]
+    fn ring_keeps_latest_candles() {
+        let mut candle_state = CandleState::default();
+        let start = 3_600 * 1_000;
+        for i in 0..40u64 {
+            candle_state.update(start + 60 * i, u128::from(i), u128::from(i), 1, 1);
+        }
+        let candles = candle_state.get_candles(CandleResolution::OneMinute);
+        assert_eq!(candles.len(), CANDLE_NUM);
+        assert_eq!({ candles[0].start_time }, start + 60 * 8);
+        assert_eq!({ candles[CANDLE_NUM - 1].start_time }, start + 60 * 39);
+        assert!(candles
+            .windows(2)
+            .all(|pair| pair[0].start_time < pair[1].start_time));
+        assert_eq!(
+            candle_state.get_candles(CandleResolution::FiveMinutes).len(),
+            8
+        );
+    }
+}
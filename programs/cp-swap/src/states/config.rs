@@ -1,9 +1,14 @@
+use crate::curve::fees::{Fees, FEE_RATE_DENOMINATOR_VALUE};
+use crate::states::AVAILABLE_AMOUNT;
+use crate::utils::math::to_decimals;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
 
-/// Holds the current owner of the factory
-#[account]
+/// Holds the current owner of the factory.
+/// Packed, so the layout matches the Borsh encoding of the accounts created before it was zero-copy
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
 #[derive(Default, Debug)]
 pub struct AmmConfig {
     /// Bump to identify PDA
@@ -26,8 +31,36 @@ pub struct AmmConfig {
     pub protocol_owner: Pubkey,
     /// Address of the fund fee owner
     pub fund_owner: Pubkey,
+    /// The token_1 -> token_0 fee right after the pool opens, denominated in hundredths of a bip (10^-6)
+    pub sniper_fee_rate: u64,
+    /// The duration in seconds after `open_time` during which the sniper fee decays, 0 to disable
+    pub sniper_duration: u64,
+    /// How the sniper fee decays to `trade_from_one_to_zero_fee_rate`, see `SniperFeeDecay`
+    pub sniper_decay_mode: u8,
+    /// The max token_0 one wallet can buy in total during the opening window, as a share of `AVAILABLE_AMOUNT`
+    /// denominated in hundredths of a bip (10^-6)
+    pub max_buy_rate: u64,
+    /// The duration in seconds after `open_time` during which `max_buy_rate` applies, 0 to disable
+    pub max_buy_duration: u64,
+    pub padding_0: [u8; 7],
     /// padding
-    pub padding: [u64; 16],
+    pub padding: [u64; 11],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SniperFeeDecay {
+    Linear,
+    Exponential,
+}
+
+impl SniperFeeDecay {
+    pub fn from_u8(value: u8) -> Option<SniperFeeDecay> {
+        match value {
+            0 => Some(SniperFeeDecay::Linear),
+            1 => Some(SniperFeeDecay::Exponential),
+            _ => None,
+        }
+    }
 }
 
 impl AmmConfig {
@@ -35,8 +68,49 @@ impl AmmConfig {
       + 1 // u8
       + 1 // bool
       + 2 // u16
-      + 8 * 5 // u64
+      + 8 * 7 // u64
       + 32 * 2 // Pubkey
-      + 8 * 16 // u64
+      + 1 // u8
+      + 8 * 2 // u64
+      + 7 // u8
+      + 8 * 11 // u64
       ;
+
+    /// The token_1 -> token_0 fee rate `elapsed` seconds after the pool opened
+    pub fn one_to_zero_fee_rate_at(&self, elapsed: u64) -> u64 {
+        Fees::sniper_fee_rate(
+            self.trade_from_one_to_zero_fee_rate,
+            self.sniper_fee_rate,
+            self.sniper_duration,
+            elapsed,
+            SniperFeeDecay::from_u8(self.sniper_decay_mode) == Some(SniperFeeDecay::Exponential),
+        )
+    }
+
+    /// The max token_0 a wallet may buy in total `elapsed` seconds after the pool opened,
+    /// `None` once the opening window is over or when the cap is disabled
+    pub fn max_buy_amount_at(&self, elapsed: u64, decimals: u32) -> Result<Option<u64>> {
+        if self.max_buy_rate == 0 || elapsed >= self.max_buy_duration {
+            return Ok(None);
+        }
+        let max_amount = u128::from(to_decimals(AVAILABLE_AMOUNT, decimals)?)
+            .checked_mul(u128::from(self.max_buy_rate))
+            .unwrap()
+            .checked_div(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+            .unwrap();
+        Ok(Some(u64::try_from(max_amount).unwrap()))
+    }
+}
+
+#[cfg(test)]
+pub mod config_test {
+    use super::*;
+
+    #[test]
+    fn amm_config_len() {
+        // the packed layout must keep the size of the Borsh accounts created before
+        assert_eq!(AmmConfig::LEN, std::mem::size_of::<AmmConfig>() + 8);
+        // the fields added since are carved from the padding, the accounts keep their size
+        assert_eq!(AmmConfig::LEN, 244);
+    }
 }
@@ -25,9 +25,9 @@ pub struct LpChangeEvent {
 pub struct SwapEvent {
     #[index]
     pub pool_id: Pubkey,
-    /// pool vault sub trade fees
+    /// virtual token_0 reserve before the trade, vault sub trade fees
     pub token_0_vault_before: u64,
-    /// pool vault sub trade fees
+    /// virtual token_1 reserve before the trade, vault sub trade fees
     pub token_1_vault_before: u64,
     /// calculate result without transfer fee
     pub input_amount: u64,
@@ -35,6 +35,24 @@ pub struct SwapEvent {
     pub output_amount: u64,
     pub base_input: bool,
     pub trade_direction: u8,
+    /// the trade fee rate applied to the swap, includes the sniper fee
+    pub trade_fee_rate: u64,
+    /// the user performing the swap
+    pub payer: Pubkey,
+    /// virtual token_0 reserve after the trade, vault sub trade fees
+    pub token_0_vault_after: u64,
+    /// virtual token_1 reserve after the trade, vault sub trade fees
+    pub token_1_vault_after: u64,
+    /// token_0 price in token_1 after the trade, Q32.32
+    pub token_0_price_x32: u128,
+    /// fees of the input token accrued to the protocol and the fund
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// native SOL the buyer paid on top of the input, to `create_pool_fee`
+    pub margin_trade_fee: u64,
+    /// native SOL held back from the seller's output, to `create_pool_fee`
+    pub padding_trade_fee: u64,
+    pub block_timestamp: u64,
 }
 
 /// Emitted when deploy pair
@@ -49,4 +67,50 @@ pub struct PreDeployPairEvent {
     /// cumulative
     pub token_0_cumulative: u128,
     pub token_1_cumulative: u128,
+    /// statistics of the pool state, which is closed at graduation
+    pub buy_volume_token_1: u64,
+    pub sell_volume_token_1: u64,
+    pub trade_count: u64,
+    pub unique_buyer_count: u64,
+    pub ath_price_x32: u128,
+    pub graduation_timestamp: u64,
+}
+
+/// Emitted when protocol fees buy back and burn token_0
+#[event]
+pub struct BuybackEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    /// protocol fees in token_1 spent on the buyback
+    pub amount_in: u64,
+    /// token_0 bought and burned
+    pub burn_amount: u64,
+    /// token_0 price in token_1 after the buyback, Q32.32
+    pub token_0_price_x32: u128,
+    pub block_timestamp: u64,
+}
+
+/// Emitted when a pool creator locks token_0, explorers can show it as a dev lock
+#[event]
+pub struct VestingCreatedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub beneficiary: Pubkey,
+    /// token_0 locked
+    pub amount: u64,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+}
+
+/// Emitted when vested token_0 is claimed
+#[event]
+pub struct VestingClaimedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub beneficiary: Pubkey,
+    /// token_0 claimed now
+    pub amount: u64,
+    /// token_0 claimed so far
+    pub claimed_amount: u64,
 }
@@ -0,0 +1,166 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Seed to derive account address and signature
+pub const FEE_SPLIT_CONFIG_SEED: &str = "fee_split_config";
+// Max number of recipients of a fee split
+pub const FEE_SPLIT_RECIPIENT_NUM: usize = 5;
+// The sum of the recipients' weights
+pub const FEE_SPLIT_BPS_DENOMINATOR: u16 = 10_000;
+
+/// A wallet and its share of the fees
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct FeeSplitRecipient {
+    pub wallet: Pubkey,
+    /// The share of the fees, denominated in bips (10^-4)
+    pub weight_bps: u16,
+}
+
+impl FeeSplitRecipient {
+    pub const LEN: usize = 32 + 2;
+}
+
+/// How the protocol and fund fees of the pools of an amm config are split
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeSplitConfig {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config the split applies to
+    pub amm_config: Pubkey,
+    /// The number of used `recipients`
+    pub recipient_count: u8,
+    pub recipients: [FeeSplitRecipient; FEE_SPLIT_RECIPIENT_NUM],
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl FeeSplitConfig {
+    pub const LEN: usize =
+        8 + 1 + 32 + 1 + FeeSplitRecipient::LEN * FEE_SPLIT_RECIPIENT_NUM + 8 * 8;
+
+    /// Replaces the recipients, the weights must be positive and add up to `FEE_SPLIT_BPS_DENOMINATOR`
+    pub fn set_recipients(&mut self, recipients: &[FeeSplitRecipient]) -> Result<()> {
+        require!(
+            !recipients.is_empty() && recipients.len() <= FEE_SPLIT_RECIPIENT_NUM,
+            ErrorCode::InvalidFeeSplit
+        );
+        let mut total_weight = 0u16;
+        for (index, recipient) in recipients.iter().enumerate() {
+            require!(recipient.weight_bps > 0, ErrorCode::InvalidFeeSplit);
+            require!(
+                recipients[..index]
+                    .iter()
+                    .all(|other| other.wallet != recipient.wallet),
+                ErrorCode::InvalidFeeSplit
+            );
+            total_weight = total_weight
+                .checked_add(recipient.weight_bps)
+                .ok_or(ErrorCode::InvalidFeeSplit)?;
+        }
+        require_eq!(
+            total_weight,
+            FEE_SPLIT_BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeSplit
+        );
+
+        self.recipients = [FeeSplitRecipient::default(); FEE_SPLIT_RECIPIENT_NUM];
+        self.recipients[..recipients.len()].copy_from_slice(recipients);
+        self.recipient_count = recipients.len() as u8;
+        Ok(())
+    }
+
+    /// The used recipients
+    pub fn recipients(&self) -> &[FeeSplitRecipient] {
+        &self.recipients[..usize::from(self.recipient_count)]
+    }
+
+    /// Splits `amount` by the recipients' weights, the rounding dust goes to the first recipient
+    pub fn split(&self, amount: u64) -> Vec<u64> {
+        let mut shares: Vec<u64> = self
+            .recipients()
+            .iter()
+            .map(|recipient| {
+                u64::try_from(
+                    u128::from(amount) * u128::from(recipient.weight_bps)
+                        / u128::from(FEE_SPLIT_BPS_DENOMINATOR),
+                )
+                .unwrap()
+            })
+            .collect();
+        let distributed: u64 = shares.iter().sum();
+        if let Some(first) = shares.first_mut() {
+            *first += amount - distributed;
+        }
+        shares
+    }
+}
+
+#[cfg(test)]
+pub mod fee_split_test {
+    use super::*;
+
+    fn recipient(weight_bps: u16) -> FeeSplitRecipient {
+        FeeSplitRecipient {
+            wallet: Pubkey::new_unique(),
+            weight_bps,
+        }
+    }
+
+    #[test]
+    fn fee_split_len() {
+        let fee_split_config = FeeSplitConfig::default();
+        assert_eq!(
+            FeeSplitConfig::LEN,
+            8 + fee_split_config.try_to_vec().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn set_recipients_checks_weights() {
+        let mut fee_split_config = FeeSplitConfig::default();
+        assert!(fee_split_config.set_recipients(&[]).is_err());
+        assert!(fee_split_config
+            .set_recipients(&[recipient(6_000), recipient(3_000)])
+            .is_err());
+        assert!(fee_split_config
+            .set_recipients(&[recipient(10_000), recipient(0)])
+            .is_err());
+        assert!(fee_split_config
+            .set_recipients(&[recipient(2_000); FEE_SPLIT_RECIPIENT_NUM + 1])
+            .is_err());
+        let duplicate = recipient(5_000);
+        assert!(fee_split_config
+            .set_recipients(&[duplicate, duplicate])
+            .is_err());
+
+        let recipients = [recipient(7_000), recipient(2_000), recipient(1_000)];
+        fee_split_config.set_recipients(&recipients).unwrap();
+        assert_eq!(fee_split_config.recipients(), &recipients);
+
+        // a shorter list clears the previous recipients
+        fee_split_config
+            .set_recipients(&[recipient(10_000)])
+            .unwrap();
+        assert_eq!(fee_split_config.recipients().len(), 1);
+        assert_eq!(fee_split_config.recipients[1], FeeSplitRecipient::default());
+    }
+
+    #[test]
+    fn split_distributes_the_whole_amount() {
+        let mut fee_split_config = FeeSplitConfig::default();
+        fee_split_config
+            .set_recipients(&[recipient(3_333), recipient(3_333), recipient(3_334)])
+            .unwrap();
+        assert_eq!(fee_split_config.split(100), vec![34, 33, 33]);
+        assert_eq!(fee_split_config.split(0), vec![0, 0, 0]);
+        assert_eq!(
+            fee_split_config
+                .split(u64::MAX)
+                .iter()
+                .map(|share| u128::from(*share))
+                .sum::<u128>(),
+            u128::from(u64::MAX)
+        );
+    }
+}
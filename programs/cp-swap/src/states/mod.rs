@@ -9,3 +9,15 @@ pub use events::*;
 
 pub mod oracle;
 pub use oracle::*;
+
+pub mod user_position;
+pub use user_position::*;
+
+pub mod candle;
+pub use candle::*;
+
+pub mod fee_split;
+pub use fee_split::*;
+
+pub mod vesting;
+pub use vesting::*;
@@ -1,13 +1,23 @@
 /// Oracle provides price data useful for a wide variety of system designs
 ///
+use crate::error::ErrorCode;
+use crate::utils::U256;
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 #[cfg(test)]
 use std::time::{SystemTime, UNIX_EPOCH};
 /// Seed to derive account address and signature
 pub const OBSERVATION_SEED: &str = "observation";
-// Number of ObservationState element
+// Number of observations stored inline in ObservationState, and the default ring size
 pub const OBSERVATION_NUM: usize = 100;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u16 = 15;
+/// The smallest ring that can produce a price
+pub const OBSERVATION_NUM_MIN: u16 = 2;
+/// The largest ring `initialize` can create, a CPI creates accounts of at most 10KiB
+pub const OBSERVATION_NUM_CREATE_MAX: u16 = (OBSERVATION_NUM
+    + (anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE
+        - ObservationState::LEN)
+        / Observation::LEN) as u16;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -25,6 +35,12 @@ impl Observation {
     pub const LEN: usize = 8 + 16 + 16;
 }
 
+// Safety: packed plain integers, read in place from the account extension like the inline array
+unsafe impl bytemuck::Pod for Observation {}
+unsafe impl bytemuck::Zeroable for Observation {}
+
+/// The observations past the inline array are stored right after the account,
+/// see `ObservationState::space` and `ObservationState::load_mut_with_extension`
 #[account(zero_copy(unsafe))]
 #[repr(packed)]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -36,8 +52,14 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// the number of observations in the ring, 0 for OBSERVATION_NUM
+    pub observation_num: u16,
+    /// the number of observations the ring grows to once it wraps around
+    pub observation_num_next: u16,
+    /// the minimum seconds between two observations, 0 for OBSERVATION_UPDATE_DURATION_DEFAULT
+    pub update_duration: u16,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u16; 13],
 }
 
 impl Default for ObservationState {
@@ -48,75 +70,337 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            observation_num: 0,
+            observation_num_next: 0,
+            update_duration: 0,
+            padding: [0u16; 13],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
+    pub const LEN: usize = 8 + 1 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 2 * 3 + 2 * 13;
+
+    /// The account size to hold `observation_num` observations
+    pub fn space(observation_num: u16) -> usize {
+        Self::LEN + Observation::LEN * usize::from(observation_num).saturating_sub(OBSERVATION_NUM)
+    }
+
+    /// Loads the account together with the observations stored after the inline array
+    pub fn load_with_extension<'a>(
+        loader: &'a AccountLoader<ObservationState>,
+    ) -> Result<(Ref<'a, ObservationState>, Ref<'a, [Observation]>)> {
+        // validates the account
+        drop(loader.load()?);
+        let data = loader.as_ref().try_borrow_data()?;
+        Ok(Ref::map_split(data, |data| {
+            let (state, extension) = data.split_at(Self::LEN);
+            let extension_len = extension.len() / Observation::LEN * Observation::LEN;
+            (
+                bytemuck::from_bytes(&state[8..]),
+                bytemuck::cast_slice(&extension[..extension_len]),
+            )
+        }))
+    }
+
+    /// Mutably loads the account together with the observations stored after the inline array
+    pub fn load_mut_with_extension<'a>(
+        loader: &'a AccountLoader<ObservationState>,
+    ) -> Result<(RefMut<'a, ObservationState>, RefMut<'a, [Observation]>)> {
+        // validates the account
+        drop(loader.load_mut()?);
+        let data = loader.as_ref().try_borrow_mut_data()?;
+        Ok(RefMut::map_split(data, |data| {
+            let (state, extension) = data.split_at_mut(Self::LEN);
+            let extension_len = extension.len() / Observation::LEN * Observation::LEN;
+            (
+                bytemuck::from_bytes_mut(&mut state[8..]),
+                bytemuck::cast_slice_mut(&mut extension[..extension_len]),
+            )
+        }))
+    }
+
+    pub fn initialize(
+        &mut self,
+        pool_id: Pubkey,
+        observation_num: u16,
+        update_duration: u16,
+    ) -> Result<()> {
+        require_gte!(
+            observation_num,
+            OBSERVATION_NUM_MIN,
+            ErrorCode::InvalidInput
+        );
+        require_gt!(update_duration, 0, ErrorCode::InvalidInput);
+        self.pool_id = pool_id;
+        self.observation_num = observation_num;
+        self.observation_num_next = observation_num;
+        self.update_duration = update_duration;
+        Ok(())
+    }
+
+    /// The number of observations in the ring
+    pub fn ring_size(&self) -> usize {
+        match self.observation_num {
+            0 => OBSERVATION_NUM,
+            observation_num => usize::from(observation_num),
+        }
+    }
+
+    /// The number of observations the ring grows to once it wraps around
+    pub fn ring_size_next(&self) -> usize {
+        self.ring_size().max(usize::from(self.observation_num_next))
+    }
+
+    /// The minimum seconds between two observations
+    pub fn update_duration(&self) -> u64 {
+        match self.update_duration {
+            0 => u64::from(OBSERVATION_UPDATE_DURATION_DEFAULT),
+            update_duration => u64::from(update_duration),
+        }
+    }
+
+    /// Schedules the ring to grow to `observation_num_next` observations once it wraps around,
+    /// the account must already be large enough
+    pub fn grow(&mut self, extension: &[Observation], observation_num_next: u16) -> Result<()> {
+        require_gt!(
+            usize::from(observation_num_next),
+            self.ring_size_next(),
+            ErrorCode::InvalidInput
+        );
+        require_gte!(
+            OBSERVATION_NUM + extension.len(),
+            usize::from(observation_num_next),
+            ErrorCode::InvalidInput
+        );
+        self.observation_num_next = observation_num_next;
+        if self.observation_num == 0 {
+            self.observation_num = OBSERVATION_NUM as u16;
+        }
+        Ok(())
+    }
+
+    fn observation(&self, extension: &[Observation], index: usize) -> Observation {
+        if index < OBSERVATION_NUM {
+            self.observations[index]
+        } else {
+            extension[index - OBSERVATION_NUM]
+        }
+    }
+
+    fn observation_mut<'a>(
+        &'a mut self,
+        extension: &'a mut [Observation],
+        index: usize,
+    ) -> &'a mut Observation {
+        if index < OBSERVATION_NUM {
+            &mut self.observations[index]
+        } else {
+            &mut extension[index - OBSERVATION_NUM]
+        }
+    }
 
-    // Writes an oracle observation to the account, returning the next observation_index.
-    /// Writable at most once per second. Index represents the most recently written element.
-    /// If the index is at the end of the allowable array length (100 - 1), the next index will turn to 0.
+    /// Accumulates the prices in effect since the latest observation, as Uniswap v2 does:
+    /// called before each trade with the prices the trade starts from, so every price counts
+    /// for exactly as long as it held.
+    /// The latest observation moves forward until it is `update_duration` seconds past the one
+    /// before, then the next update writes a new observation. If the index is at the end of the
+    /// ring, the next index will turn to 0, unless the ring grows.
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extension` - The observations stored after the account
     /// * `block_timestamp` - The current timestamp of to update
-    /// * `token_0_price_x32` - The token_0_price_x32 at the time of the new observation
-    /// * `token_1_price_x32` - The token_1_price_x32 at the time of the new observation
-    /// * `observation_index` - The last update index of element in the oracle array
-    ///
-    /// # Return
-    /// * `next_observation_index` - The new index of element to update in the oracle array
+    /// * `token_0_price_x32` - The token_0_price_x32 since the latest observation, before the trade
+    /// * `token_1_price_x32` - The token_1_price_x32 since the latest observation, before the trade
     ///
     pub fn update(
         &mut self,
+        extension: &mut [Observation],
         block_timestamp: u64,
         token_0_price_x32: u128,
         token_1_price_x32: u128,
     ) {
-        let observation_index = self.observation_index;
+        let observation_index = usize::from(self.observation_index);
         if !self.initialized {
             self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].cumulative_token_0_price_x32 = 0;
-            self.observations[observation_index as usize].cumulative_token_1_price_x32 = 0;
+            let observation = self.observation_mut(extension, observation_index);
+            observation.block_timestamp = block_timestamp;
+            observation.cumulative_token_0_price_x32 = 0;
+            observation.cumulative_token_1_price_x32 = 0;
+            return;
+        }
+        let last_observation = self.observation(extension, observation_index);
+        let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
+        if delta_time == 0 {
+            return;
+        }
+        let delta_token_0_price_x32 = token_0_price_x32.checked_mul(delta_time.into()).unwrap();
+        let delta_token_1_price_x32 = token_1_price_x32.checked_mul(delta_time.into()).unwrap();
+        let next_observation_index = if !self.is_latest_sealed(extension) {
+            observation_index
+        } else if observation_index + 1 < self.ring_size() {
+            observation_index + 1
+        } else if self.ring_size_next() > self.ring_size() {
+            // grow into the slots added by `grow_observation` instead of wrapping around
+            self.observation_num = self.observation_num_next;
+            observation_index + 1
         } else {
-            let last_observation = self.observations[observation_index as usize];
-            let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
-            if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT as u64 {
-                return;
-            }
-            let delta_token_0_price_x32 = token_0_price_x32.checked_mul(delta_time.into()).unwrap();
-            let delta_token_1_price_x32 = token_1_price_x32.checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
-                0
-            } else {
-                observation_index + 1
-            };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            // cumulative_token_price_x32 only occupies the first 64 bits, and the remaining 64 bits are used to store overflow data
-            self.observations[next_observation_index as usize].cumulative_token_0_price_x32 =
-                last_observation
-                    .cumulative_token_0_price_x32
-                    .wrapping_add(delta_token_0_price_x32);
-            self.observations[next_observation_index as usize].cumulative_token_1_price_x32 =
-                last_observation
-                    .cumulative_token_1_price_x32
-                    .wrapping_add(delta_token_1_price_x32);
-            self.observation_index = next_observation_index;
+            0
+        };
+        let next_observation = self.observation_mut(extension, next_observation_index);
+        next_observation.block_timestamp = block_timestamp;
+        // cumulative_token_price_x32 only occupies the first 64 bits, and the remaining 64 bits are used to store overflow data
+        next_observation.cumulative_token_0_price_x32 = last_observation
+            .cumulative_token_0_price_x32
+            .wrapping_add(delta_token_0_price_x32);
+        next_observation.cumulative_token_1_price_x32 = last_observation
+            .cumulative_token_1_price_x32
+            .wrapping_add(delta_token_1_price_x32);
+        self.observation_index = next_observation_index as u16;
+    }
+
+    /// Whether the latest observation is `update_duration` seconds past the one before,
+    /// the first observation always is
+    fn is_latest_sealed(&self, extension: &[Observation]) -> bool {
+        let (_, len) = self.oldest_index_and_len(extension);
+        if len < 2 {
+            return true;
         }
+        let observation_index = usize::from(self.observation_index);
+        let previous_index = observation_index
+            .checked_sub(1)
+            .unwrap_or(self.ring_size() - 1);
+        let latest = self.observation(extension, observation_index);
+        let previous = self.observation(extension, previous_index);
+        latest.block_timestamp - previous.block_timestamp >= self.update_duration()
     }
 
-    pub fn get_latest_cumulative(&self) -> (u128, u128) {
-        let observation_index = self.observation_index;
+    pub fn get_latest_cumulative(&self, extension: &[Observation]) -> (u128, u128) {
+        let observation = self.observation(extension, usize::from(self.observation_index));
         (
-            self.observations[observation_index as usize].cumulative_token_0_price_x32,
-            self.observations[observation_index as usize].cumulative_token_1_price_x32,
+            observation.cumulative_token_0_price_x32,
+            observation.cumulative_token_1_price_x32,
         )
     }
+
+    /// The index of the oldest observation and the number of observations written
+    fn oldest_index_and_len(&self, extension: &[Observation]) -> (usize, usize) {
+        if !self.initialized {
+            return (0, 0);
+        }
+        let observation_index = usize::from(self.observation_index);
+        let next_index = (observation_index + 1) % self.ring_size();
+        if self.observation(extension, next_index).block_timestamp == 0 {
+            (0, observation_index + 1)
+        } else {
+            (next_index, self.ring_size())
+        }
+    }
+
+    /// Returns the cumulative prices `seconds_ago` seconds before `block_timestamp`.
+    /// Interpolates linearly between the two surrounding observations. Past the latest
+    /// observation the prices are the current ones, which held since the latest trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations stored after the account
+    /// * `block_timestamp` - The current timestamp
+    /// * `seconds_ago` - How far back to look from `block_timestamp`
+    /// * `current_prices` - The token_0 and token_1 prices of the pool reserves now, Q32.32
+    ///
+    pub fn observe(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u64,
+        seconds_ago: u64,
+        current_prices: (u128, u128),
+    ) -> Result<(u128, u128)> {
+        let (oldest_index, len) = self.oldest_index_and_len(extension);
+        require_gt!(len, 0, ErrorCode::InsufficientObservations);
+        let ring_size = self.ring_size();
+        let observation_at = |i: usize| self.observation(extension, (oldest_index + i) % ring_size);
+        let target = block_timestamp
+            .checked_sub(seconds_ago)
+            .ok_or(ErrorCode::InsufficientObservations)?;
+
+        let latest = observation_at(len - 1);
+        if target >= latest.block_timestamp {
+            let elapsed = u128::from(target - latest.block_timestamp);
+            return Ok((
+                latest
+                    .cumulative_token_0_price_x32
+                    .wrapping_add(current_prices.0.checked_mul(elapsed).unwrap()),
+                latest
+                    .cumulative_token_1_price_x32
+                    .wrapping_add(current_prices.1.checked_mul(elapsed).unwrap()),
+            ));
+        }
+
+        require_gte!(
+            target,
+            observation_at(0).block_timestamp,
+            ErrorCode::InsufficientObservations
+        );
+        // find the last observation at or before the target
+        let (mut low, mut high) = (0usize, len - 1);
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if observation_at(mid).block_timestamp <= target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        let before = observation_at(low);
+        Ok(interpolate(
+            &before,
+            &observation_at(high),
+            target - before.block_timestamp,
+        ))
+    }
+
+    /// Returns the time weighted average prices over the last `window_secs` seconds, Q32.32,
+    /// `current_prices` as for `observe`
+    pub fn twap(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u64,
+        window_secs: u64,
+        current_prices: (u128, u128),
+    ) -> Result<(u128, u128)> {
+        require_gt!(window_secs, 0, ErrorCode::InvalidInput);
+        let (end_token_0_cumulative, end_token_1_cumulative) =
+            self.observe(extension, block_timestamp, 0, current_prices)?;
+        let (start_token_0_cumulative, start_token_1_cumulative) =
+            self.observe(extension, block_timestamp, window_secs, current_prices)?;
+        Ok((
+            end_token_0_cumulative.wrapping_sub(start_token_0_cumulative) / u128::from(window_secs),
+            end_token_1_cumulative.wrapping_sub(start_token_1_cumulative) / u128::from(window_secs),
+        ))
+    }
+}
+
+/// Cumulative prices `elapsed` seconds after `before`, at the average prices between `before` and `after`
+fn interpolate(before: &Observation, after: &Observation, elapsed: u64) -> (u128, u128) {
+    let duration = after.block_timestamp - before.block_timestamp;
+    let extend = |before_cumulative: u128, after_cumulative: u128| -> u128 {
+        let delta = (U256::from(after_cumulative.wrapping_sub(before_cumulative))
+            * U256::from(elapsed))
+            / U256::from(duration);
+        before_cumulative.wrapping_add(delta.as_u128())
+    };
+    (
+        extend(
+            before.cumulative_token_0_price_x32,
+            after.cumulative_token_0_price_x32,
+        ),
+        extend(
+            before.cumulative_token_1_price_x32,
+            after.cumulative_token_1_price_x32,
+        ),
+    )
 }
 
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
@@ -132,3 +416,467 @@ pub fn block_timestamp_mock() -> u64 {
         .unwrap()
         .as_secs()
 }
+
+#[cfg(test)]
+pub mod oracle_test {
+    use super::*;
+    use crate::states::Q32;
+
+    fn observation_state_with(start: u64, prices: &[(u64, u128, u128)]) -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        observation_state.update(&mut [], start, 0, 0);
+        for (timestamp, token_0_price_x32, token_1_price_x32) in prices {
+            observation_state.update(&mut [], *timestamp, *token_0_price_x32, *token_1_price_x32);
+        }
+        observation_state
+    }
+
+    #[test]
+    fn twap_of_constant_price() {
+        let now = block_timestamp_mock();
+        let start = now - 600;
+        let prices: Vec<(u64, u128, u128)> = (1..=40)
+            .map(|i| (start + i * 15, 3 * Q32, Q32 / 3))
+            .collect();
+        let observation_state = observation_state_with(start, &prices);
+
+        assert_eq!(
+            observation_state
+                .twap(&[], now, 600, (3 * Q32, Q32 / 3))
+                .unwrap(),
+            (3 * Q32, Q32 / 3)
+        );
+        assert_eq!(
+            observation_state
+                .twap(&[], now, 100, (3 * Q32, Q32 / 3))
+                .unwrap(),
+            (3 * Q32, Q32 / 3)
+        );
+        assert_eq!(
+            observation_state
+                .twap(&[], now, 7, (3 * Q32, Q32 / 3))
+                .unwrap(),
+            (3 * Q32, Q32 / 3)
+        );
+    }
+
+    #[test]
+    fn observe_interpolates_between_observations() {
+        let now = block_timestamp_mock();
+        let start = now - 60;
+        // 100 from start to start + 20, then 200 until start + 60
+        let observation_state =
+            observation_state_with(start, &[(start + 20, 100, 10), (start + 60, 200, 20)]);
+        let current_prices = (300, 30);
+
+        let observe = |seconds_ago| {
+            observation_state
+                .observe(&[], now, seconds_ago, current_prices)
+                .unwrap()
+        };
+        assert_eq!(observe(60), (0, 0));
+        assert_eq!(observe(50), (1_000, 100));
+        assert_eq!(observe(40), (2_000, 200));
+        assert_eq!(observe(30), (4_000, 400));
+        assert_eq!(observe(0), (10_000, 1_000));
+        // 40s at 200 + 10s at 100
+        assert_eq!(
+            observation_state
+                .twap(&[], now, 50, current_prices)
+                .unwrap(),
+            (180, 18)
+        );
+    }
+
+    #[test]
+    fn observe_past_latest_observation_at_current_prices() {
+        let now = block_timestamp_mock();
+        let start = now - 100;
+        let observation_state =
+            observation_state_with(start, &[(start + 20, 100, 10), (start + 60, 200, 20)]);
+
+        // the current prices held since the latest trade, not the average before it
+        assert_eq!(
+            observation_state.observe(&[], now, 0, (300, 30)).unwrap(),
+            (22_000, 2_200)
+        );
+        assert_eq!(
+            observation_state.twap(&[], now, 40, (300, 30)).unwrap(),
+            (300, 30)
+        );
+        // 40s at 300 + 40s at 200
+        assert_eq!(
+            observation_state.twap(&[], now, 80, (300, 30)).unwrap(),
+            (250, 25)
+        );
+    }
+
+    #[test]
+    fn observe_wrapped_ring() {
+        let now = block_timestamp_mock();
+        let start = now - 15 * 250;
+        let prices: Vec<(u64, u128, u128)> = (1..=250)
+            .map(|i| (start + i * 15, u128::from(i), u128::from(i) * 2))
+            .collect();
+        let observation_state = observation_state_with(start, &prices);
+        assert_eq!({ observation_state.observation_index }, 50);
+
+        // 15s at each of 245..=250
+        assert_eq!(
+            observation_state.twap(&[], now, 90, (250, 500)).unwrap(),
+            (1_485 / 6, 2_970 / 6)
+        );
+        // the oldest observation left is at 151 * 15 seconds
+        assert!(observation_state
+            .observe(&[], now, 15 * 99, (250, 500))
+            .is_ok());
+        assert!(observation_state
+            .observe(&[], now, 15 * 99 + 1, (250, 500))
+            .is_err());
+    }
+
+    #[test]
+    fn observe_without_enough_observations() {
+        let now = block_timestamp_mock();
+        let observation_state = ObservationState::default();
+        assert!(observation_state.observe(&[], now, 0, (7, 70)).is_err());
+
+        // the first observation is enough, the current prices held since
+        let observation_state = observation_state_with(now - 10, &[]);
+        assert_eq!(
+            observation_state.observe(&[], now, 10, (7, 70)).unwrap(),
+            (0, 0)
+        );
+        assert_eq!(
+            observation_state.observe(&[], now, 0, (7, 70)).unwrap(),
+            (70, 700)
+        );
+        assert!(observation_state.observe(&[], now, 11, (7, 70)).is_err());
+        assert!(observation_state.twap(&[], now, 0, (7, 70)).is_err());
+    }
+
+    #[test]
+    fn update_with_configured_duration() {
+        let now = block_timestamp_mock();
+        let mut observation_state = ObservationState::default();
+        observation_state
+            .initialize(Pubkey::default(), 10, 60)
+            .unwrap();
+        observation_state.update(&mut [], now, 0, 0);
+        observation_state.update(&mut [], now + 30, 100, 10);
+        assert_eq!({ observation_state.observation_index }, 1);
+        // the latest observation moves forward until it is 60 seconds past the one before
+        observation_state.update(&mut [], now + 45, 100, 10);
+        observation_state.update(&mut [], now + 45, 500, 50);
+        observation_state.update(&mut [], now + 60, 100, 10);
+        assert_eq!({ observation_state.observation_index }, 1);
+        assert_eq!(observation_state.get_latest_cumulative(&[]), (6_000, 600));
+
+        // wraps around the 10 observations ring
+        for i in 2..=10 {
+            observation_state.update(&mut [], now + 60 * i, 100, 10);
+        }
+        assert_eq!({ observation_state.observation_index }, 0);
+        assert!(observation_state
+            .observe(&[], now + 600, 540, (100, 10))
+            .is_ok());
+        assert!(observation_state
+            .observe(&[], now + 600, 541, (100, 10))
+            .is_err());
+    }
+
+    #[test]
+    fn grow_into_extension() {
+        let now = block_timestamp_mock();
+        let mut observation_state = ObservationState::default();
+        observation_state
+            .initialize(Pubkey::default(), 3, 15)
+            .unwrap();
+        let mut extension = vec![Observation::default(); 5];
+        let mut timestamp = now;
+        let mut update = |observation_state: &mut ObservationState,
+                          extension: &mut [Observation]| {
+            observation_state.update(extension, timestamp, Q32, Q32);
+            timestamp += 15;
+        };
+        for _ in 0..5 {
+            update(&mut observation_state, &mut extension);
+        }
+        assert_eq!({ observation_state.observation_index }, 1);
+
+        // the account must hold the grown ring
+        assert!(observation_state.grow(&extension, 106).is_err());
+        assert!(observation_state.grow(&extension, 3).is_err());
+        observation_state.grow(&extension, 105).unwrap();
+        assert_eq!(observation_state.ring_size(), 3);
+
+        // the ring keeps its order and grows once it reaches the end
+        update(&mut observation_state, &mut extension);
+        assert_eq!({ observation_state.observation_index }, 2);
+        update(&mut observation_state, &mut extension);
+        assert_eq!({ observation_state.observation_index }, 3);
+        assert_eq!(observation_state.ring_size(), 105);
+        let latest = now + 15 * 6;
+        assert_eq!(
+            observation_state.twap(&[], latest, 45, (Q32, Q32)).unwrap(),
+            (Q32, Q32)
+        );
+        assert!(observation_state
+            .observe(&[], latest, 46, (Q32, Q32))
+            .is_err());
+
+        for _ in 0..110 {
+            update(&mut observation_state, &mut extension);
+        }
+        assert_eq!({ observation_state.observation_index }, 8);
+        assert_ne!({ extension[4].block_timestamp }, 0);
+        let latest = timestamp - 15;
+        assert_eq!(
+            observation_state
+                .twap(&extension, latest, 15 * 104, (Q32, Q32))
+                .unwrap(),
+            (Q32, Q32)
+        );
+        assert!(observation_state
+            .observe(&extension, latest, 15 * 104 + 1, (Q32, Q32))
+            .is_err());
+    }
+
+    #[test]
+    fn observation_space() {
+        assert_eq!(OBSERVATION_NUM_CREATE_MAX, 254);
+        assert!(ObservationState::space(OBSERVATION_NUM_CREATE_MAX) <= 10240);
+        assert!(ObservationState::space(OBSERVATION_NUM_CREATE_MAX + 1) > 10240);
+        assert_eq!(
+            ObservationState::LEN,
+            8 + std::mem::size_of::<ObservationState>()
+        );
+        assert_eq!(ObservationState::space(2), ObservationState::LEN);
+        assert_eq!(
+            ObservationState::space(OBSERVATION_NUM as u16),
+            ObservationState::LEN
+        );
+        assert_eq!(
+            ObservationState::space(OBSERVATION_NUM as u16 + 256),
+            ObservationState::LEN + 10240
+        );
+    }
+}
+
+#[cfg(test)]
+mod oracle_price_test {
+    use super::*;
+    use crate::curve::ConstantProductCurve;
+    use crate::states::{
+        PoolState, AVAILABLE_AMOUNT, BASE_INIT_TOKEN_1_AMOUNT, FROZEN_AMOUNT, Q32,
+    };
+    use crate::utils::to_decimals;
+
+    const DECIMALS: u32 = 6;
+    const VAULT_1_RENT: u64 = 890_880;
+
+    enum Trade {
+        /// lamports in, lamports of fee
+        Buy(u64, u64),
+        /// tokens in, tokens of fee
+        Sell(u64, u64),
+    }
+
+    /// The pool as the swap instructions see it, raw vault balances with accrued fees
+    struct Pool {
+        pool_state: PoolState,
+        vault_0: u64,
+        vault_1: u64,
+    }
+
+    /// The virtual reserves of the bonding curve, tracked without any vault or fee
+    struct ReferenceModel {
+        token_0_reserve: u128,
+        token_1_reserve: u128,
+        last_timestamp: u64,
+        cumulative_token_0_price_x32: u128,
+        cumulative_token_1_price_x32: u128,
+    }
+
+    impl ReferenceModel {
+        /// Accumulates the prices of the reserves since the last trade, called before a trade
+        fn record(&mut self, block_timestamp: u64) {
+            let delta_time = block_timestamp - self.last_timestamp;
+            self.cumulative_token_0_price_x32 +=
+                self.token_1_reserve * Q32 / self.token_0_reserve * u128::from(delta_time);
+            self.cumulative_token_1_price_x32 +=
+                self.token_0_reserve * Q32 / self.token_1_reserve * u128::from(delta_time);
+            self.last_timestamp = block_timestamp;
+        }
+    }
+
+    /// Replays the trades against the pool and the model, checking the oracle before each one
+    fn replay(start: u64, trades: &[(u64, Trade)]) -> (Pool, ReferenceModel, ObservationState) {
+        let frozen_amount = to_decimals(FROZEN_AMOUNT, DECIMALS).unwrap();
+        let mut pool = Pool {
+            pool_state: PoolState::default(),
+            vault_0: frozen_amount + to_decimals(AVAILABLE_AMOUNT, DECIMALS).unwrap(),
+            vault_1: VAULT_1_RENT,
+        };
+        let mut model = ReferenceModel {
+            token_0_reserve: u128::from(to_decimals(AVAILABLE_AMOUNT, DECIMALS).unwrap()),
+            token_1_reserve: u128::from(VAULT_1_RENT + BASE_INIT_TOKEN_1_AMOUNT),
+            last_timestamp: start,
+            cumulative_token_0_price_x32: 0,
+            cumulative_token_1_price_x32: 0,
+        };
+        let mut observation_state = ObservationState::default();
+        observation_state.update(&mut [], start, 0, 0);
+
+        for (block_timestamp, trade) in trades {
+            // as the swap instructions do, the oracle sees the prices before the trade
+            let (token_0_price_before, token_1_price_before) =
+                pool.pool_state
+                    .token_price_x32(pool.vault_0, pool.vault_1, frozen_amount);
+            observation_state.update(
+                &mut [],
+                *block_timestamp,
+                token_0_price_before,
+                token_1_price_before,
+            );
+            model.record(*block_timestamp);
+            assert_eq!(
+                observation_state.get_latest_cumulative(&[]),
+                (
+                    model.cumulative_token_0_price_x32,
+                    model.cumulative_token_1_price_x32
+                )
+            );
+
+            match *trade {
+                Trade::Buy(amount_in, fee) => {
+                    let amount_out = ConstantProductCurve::swap_base_input_without_fees(
+                        u128::from(amount_in),
+                        model.token_1_reserve,
+                        model.token_0_reserve,
+                    );
+                    model.token_1_reserve += u128::from(amount_in);
+                    model.token_0_reserve -= amount_out;
+                    pool.vault_1 += amount_in + fee;
+                    pool.vault_0 -= u64::try_from(amount_out).unwrap();
+                    pool.pool_state.protocol_fees_token_1 += fee;
+                }
+                Trade::Sell(amount_in, fee) => {
+                    let amount_out = ConstantProductCurve::swap_base_input_without_fees(
+                        u128::from(amount_in),
+                        model.token_0_reserve,
+                        model.token_1_reserve,
+                    );
+                    model.token_0_reserve += u128::from(amount_in);
+                    model.token_1_reserve -= amount_out;
+                    pool.vault_0 += amount_in + fee;
+                    pool.vault_1 -= u64::try_from(amount_out).unwrap();
+                    pool.pool_state.protocol_fees_token_0 += fee;
+                }
+            }
+
+            let (token_0_price_x32, token_1_price_x32) =
+                pool.pool_state
+                    .token_price_x32(pool.vault_0, pool.vault_1, frozen_amount);
+            // token_0 is priced in token_1 whatever the direction of the trade
+            assert_eq!(
+                (token_0_price_x32, token_1_price_x32),
+                (
+                    model.token_1_reserve * Q32 / model.token_0_reserve,
+                    model.token_0_reserve * Q32 / model.token_1_reserve
+                )
+            );
+            match trade {
+                Trade::Buy(..) => assert!(token_0_price_x32 > token_0_price_before),
+                Trade::Sell(..) => assert!(token_0_price_x32 < token_0_price_before),
+            }
+        }
+        (pool, model, observation_state)
+    }
+
+    #[test]
+    fn replay_alternating_buys_and_sells() {
+        let start = block_timestamp_mock();
+        let sol = anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+        replay(
+            start,
+            &[
+                (start + 15, Trade::Buy(sol, sol / 100)),
+                (
+                    start + 30,
+                    Trade::Sell(to_decimals(1_000_000, DECIMALS).unwrap(), 10_000),
+                ),
+                (start + 60, Trade::Buy(5 * sol, 5 * sol / 100)),
+                (
+                    start + 61,
+                    Trade::Sell(to_decimals(50_000, DECIMALS).unwrap(), 500),
+                ),
+                (
+                    start + 90,
+                    Trade::Sell(to_decimals(9_000_000, DECIMALS).unwrap(), 90_000),
+                ),
+                (start + 120, Trade::Buy(sol / 2, 0)),
+                (start + 300, Trade::Buy(20 * sol, sol / 5)),
+                (start + 301, Trade::Buy(sol, sol / 100)),
+                (
+                    start + 400,
+                    Trade::Sell(to_decimals(100_000_000, DECIMALS).unwrap(), 0),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn replay_runs_of_buys_then_sells() {
+        let start = block_timestamp_mock();
+        let sol = anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+        let mut trades = Vec::new();
+        let mut block_timestamp = start;
+        for i in 1..=20 {
+            block_timestamp += 7 * i % 40 + 1;
+            trades.push((block_timestamp, Trade::Buy(i * sol / 4, i * sol / 400)));
+        }
+        for i in 1..=20 {
+            block_timestamp += 11 * i % 30 + 1;
+            trades.push((
+                block_timestamp,
+                Trade::Sell(to_decimals(i * 2_000_000, DECIMALS).unwrap(), i * 1_000),
+            ));
+        }
+        replay(start, &trades);
+    }
+
+    #[test]
+    fn big_trade_before_read_moves_twap_by_its_duration() {
+        let start = block_timestamp_mock();
+        let sol = anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+        let window_secs = 600;
+        let (pool, mut model, observation_state) = replay(
+            start,
+            &[(start + window_secs - 1, Trade::Buy(50 * sol, sol / 2))],
+        );
+        let price_before = model.cumulative_token_0_price_x32 / u128::from(window_secs - 1);
+        let frozen_amount = to_decimals(FROZEN_AMOUNT, DECIMALS).unwrap();
+        let current_prices =
+            pool.pool_state
+                .token_price_x32(pool.vault_0, pool.vault_1, frozen_amount);
+        assert!(current_prices.0 > 2 * price_before);
+
+        let (token_0_twap_x32, token_1_twap_x32) = observation_state
+            .twap(&[], start + window_secs, window_secs, current_prices)
+            .unwrap();
+        model.record(start + window_secs);
+        assert_eq!(
+            (token_0_twap_x32, token_1_twap_x32),
+            (
+                model.cumulative_token_0_price_x32 / u128::from(window_secs),
+                model.cumulative_token_1_price_x32 / u128::from(window_secs)
+            )
+        );
+        // the new price held for 1 second of the window
+        assert_eq!(
+            token_0_twap_x32,
+            price_before + (current_prices.0 - price_before) / u128::from(window_secs)
+        );
+    }
+}
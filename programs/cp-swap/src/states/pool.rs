@@ -11,6 +11,10 @@ pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
 pub const MIN_TOKEN_0_MARKET_CAP: u64 = 50_000;
 pub const FROZEN_AMOUNT: u64 = 200_000_000;
 pub const AVAILABLE_AMOUNT: u64 = 800_000_000;
+// Min seconds between two buybacks of a pool
+pub const BUYBACK_MIN_INTERVAL: u64 = 60 * 60;
+// Layout version of new pools, older pools are upgraded by `migrate_pool_state`
+pub const POOL_STATE_VERSION: u8 = 1;
 pub const BASE_INIT_TOKEN_1_AMOUNT: u64 =
     24 * anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL; // 24 (virtual) SOL
 pub const MIN_AMOUNT_TO_DEPLOY: u64 =
@@ -78,12 +82,36 @@ pub struct PoolState {
 
     /// The timestamp allowed for swap in the pool at the next second(open_time + 1).
     pub open_time: u64,
+    /// The timestamp the whitelisted presale opens, the presale runs until `open_time`
+    pub presale_open_time: u64,
+    /// Merkle root of the (payer, allocation) leaves allowed to buy in the presale, zero if there is no presale
+    pub presale_merkle_root: [u8; 32],
+    /// token_1 paid into the vault by buys
+    pub buy_volume_token_1: u64,
+    /// token_1 paid out of the vault to sells
+    pub sell_volume_token_1: u64,
+    /// number of swaps
+    pub trade_count: u64,
+    /// number of wallets that bought at least once, a wallet closing its `UserPosition`
+    /// counts again on its next buy
+    pub unique_buyer_count: u64,
+    /// highest token_0 price in token_1 after a swap, Q32.32
+    pub ath_price_x32: u128,
+    /// The timestamp of the last buyback, 0 if there was none
+    pub last_buyback_time: u64,
+    /// Layout version of the account, 0 for pools created before versioning
+    pub version: u8,
+    /// Whether the pool creator opted in to `buyback_and_burn`, 1 if so
+    pub buyback_enabled: u8,
+    pub padding_0: [u8; 6],
+    /// The candle account created by `create_candle_state`, zero until then
+    pub candle_key: Pubkey,
     /// padding for future updates
-    pub padding: [u64; 32],
+    pub padding: [u64; 14],
 }
 
 impl PoolState {
-    pub const LEN: usize = 8 + 8 + 9 * 32 + 1 * 5 + 8 * 6 + 8 * 32;
+    pub const LEN: usize = 8 + 8 * 32 + 1 * 5 + 8 * 12 + 32 + 16 + 1 + 1 + 6 + 32 + 8 * 14;
 
     pub fn initialize(
         &mut self,
@@ -118,7 +146,43 @@ impl PoolState {
         self.fund_fees_token_0 = 0;
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
-        self.padding = [0u64; 32];
+        self.presale_open_time = 0;
+        self.presale_merkle_root = [0u8; 32];
+        self.buy_volume_token_1 = 0;
+        self.sell_volume_token_1 = 0;
+        self.trade_count = 0;
+        self.unique_buyer_count = 0;
+        self.ath_price_x32 = 0;
+        self.last_buyback_time = 0;
+        self.version = POOL_STATE_VERSION;
+        self.buyback_enabled = 0;
+        self.padding_0 = [0u8; 6];
+        self.candle_key = Pubkey::default();
+        self.padding = [0u64; 14];
+    }
+
+    /// Upgrades the fields of an older layout in place, one version at a time.
+    /// Returns false if the pool is already at `POOL_STATE_VERSION`
+    pub fn migrate(&mut self) -> Result<bool> {
+        require_gte!(
+            POOL_STATE_VERSION,
+            self.version,
+            crate::error::ErrorCode::InvalidPoolStateVersion
+        );
+        if self.version == POOL_STATE_VERSION {
+            return Ok(false);
+        }
+        while self.version < POOL_STATE_VERSION {
+            match self.version {
+                // the version 1 fields were carved from the zeroed padding of version 0
+                // at the same offsets. A pool trading before its migration already
+                // counts into them, so they are kept
+                0 => {}
+                _ => unreachable!(),
+            }
+            self.version += 1;
+        }
+        Ok(true)
     }
 
     pub fn set_status(&mut self, status: u8) {
@@ -141,6 +205,49 @@ impl PoolState {
         self.status.bitand(status) == 0
     }
 
+    /// Whether only whitelisted buys are allowed at `block_timestamp`
+    pub fn is_presale_active(&self, block_timestamp: u64) -> bool {
+        self.presale_merkle_root != [0u8; 32]
+            && block_timestamp > self.presale_open_time
+            && block_timestamp <= self.open_time
+    }
+
+    /// Whether a buyback may run at `block_timestamp`, at most one per `BUYBACK_MIN_INTERVAL`
+    pub fn is_buyback_allowed(&self, block_timestamp: u64) -> bool {
+        self.last_buyback_time == 0
+            || block_timestamp >= self.last_buyback_time.saturating_add(BUYBACK_MIN_INTERVAL)
+    }
+
+    /// Records a swap in the pool statistics
+    ///
+    /// # Arguments
+    ///
+    /// * `is_buy` - Whether token_0 was bought
+    /// * `amount_1` - The token_1 moved through the vault
+    /// * `token_0_price_x32` - The token_0 price after the swap
+    /// * `is_new_buyer` - Whether it is the first buy of the wallet
+    ///
+    pub fn record_swap(
+        &mut self,
+        is_buy: bool,
+        amount_1: u64,
+        token_0_price_x32: u128,
+        is_new_buyer: bool,
+    ) {
+        if is_buy {
+            self.buy_volume_token_1 = self.buy_volume_token_1.saturating_add(amount_1);
+        } else {
+            self.sell_volume_token_1 = self.sell_volume_token_1.saturating_add(amount_1);
+        }
+        if is_new_buyer {
+            self.unique_buyer_count += 1;
+        }
+        self.trade_count += 1;
+        if token_0_price_x32 > self.ath_price_x32 {
+            self.ath_price_x32 = token_0_price_x32;
+        }
+    }
+
     pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> (u64, u64) {
         (
             vault_0
@@ -152,8 +259,23 @@ impl PoolState {
         )
     }
 
-    pub fn token_price_x32(&self, vault_0: u64, vault_1: u64) -> (u128, u128) {
+    /// The reserves the bonding curve trades against: the vaults without fees,
+    /// less the frozen token_0 supply and plus the virtual token_1 liquidity
+    pub fn virtual_reserves(&self, vault_0: u64, vault_1: u64, frozen_amount: u64) -> (u64, u64) {
         let (token_0_amount, token_1_amount) = self.vault_amount_without_fee(vault_0, vault_1);
+        (
+            token_0_amount.checked_sub(frozen_amount).unwrap(),
+            token_1_amount
+                .checked_add(BASE_INIT_TOKEN_1_AMOUNT)
+                .unwrap(),
+        )
+    }
+
+    /// The oracle prices from the virtual reserves, whatever the trade direction, Q32.32:
+    /// token_1 per token_0 and token_0 per token_1
+    pub fn token_price_x32(&self, vault_0: u64, vault_1: u64, frozen_amount: u64) -> (u128, u128) {
+        let (token_0_amount, token_1_amount) =
+            self.virtual_reserves(vault_0, vault_1, frozen_amount);
         (
             token_1_amount as u128 * Q32 as u128 / token_0_amount as u128,
             token_0_amount as u128 * Q32 as u128 / token_1_amount as u128,
@@ -164,6 +286,172 @@ impl PoolState {
 #[cfg(test)]
 pub mod pool_test {
     use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn pool_state_len() {
+        assert_eq!(PoolState::LEN, std::mem::size_of::<PoolState>() + 8);
+    }
+
+    /// The fields of pools created before versioning, Borsh writes them as the packed layout
+    #[derive(AnchorSerialize)]
+    struct PoolStateV0 {
+        amm_config: Pubkey,
+        vault_1_bump: u8,
+        pool_creator: Pubkey,
+        token_0_vault: Pubkey,
+        token_1_vault: Pubkey,
+        token_0_mint: Pubkey,
+        token_0_program: Pubkey,
+        token_1_program: Pubkey,
+        observation_key: Pubkey,
+        auth_bump: u8,
+        status: u8,
+        mint_0_decimals: u8,
+        mint_1_decimals: u8,
+        lp_supply: u64,
+        protocol_fees_token_0: u64,
+        protocol_fees_token_1: u64,
+        fund_fees_token_0: u64,
+        fund_fees_token_1: u64,
+        open_time: u64,
+        padding: [u64; 32],
+    }
+
+    #[test]
+    fn migrate_version_0_account() {
+        // the account size of version 0, its `LEN` counted 40 bytes too many
+        const VERSION_0_LEN: usize = 8 + 8 + 9 * 32 + 1 * 5 + 8 * 6 + 8 * 32;
+        let v0 = PoolStateV0 {
+            amm_config: Pubkey::new_unique(),
+            vault_1_bump: 254,
+            pool_creator: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            token_0_mint: Pubkey::new_unique(),
+            token_0_program: Pubkey::new_unique(),
+            token_1_program: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            auth_bump: 253,
+            status: 1,
+            mint_0_decimals: 6,
+            mint_1_decimals: 9,
+            lp_supply: 1_000,
+            protocol_fees_token_0: 11,
+            protocol_fees_token_1: 12,
+            fund_fees_token_0: 13,
+            fund_fees_token_1: 14,
+            open_time: 1_700_000_000,
+            padding: [0u64; 32],
+        };
+        let mut data = vec![0u8; VERSION_0_LEN];
+        data[..8].copy_from_slice(&PoolState::discriminator());
+        let fields = v0.try_to_vec().unwrap();
+        data[8..8 + fields.len()].copy_from_slice(&fields);
+
+        let pool_state: &mut PoolState = bytemuck::from_bytes_mut(&mut data[8..PoolState::LEN]);
+        assert_eq!({ pool_state.version }, 0);
+        assert!(pool_state.migrate().unwrap());
+        assert_eq!({ pool_state.version }, POOL_STATE_VERSION);
+
+        // the fields of version 0 are kept
+        assert_eq!({ pool_state.amm_config }, v0.amm_config);
+        assert_eq!({ pool_state.vault_1_bump }, v0.vault_1_bump);
+        assert_eq!({ pool_state.pool_creator }, v0.pool_creator);
+        assert_eq!({ pool_state.token_0_vault }, v0.token_0_vault);
+        assert_eq!({ pool_state.token_1_vault }, v0.token_1_vault);
+        assert_eq!({ pool_state.token_0_mint }, v0.token_0_mint);
+        assert_eq!({ pool_state.token_0_program }, v0.token_0_program);
+        assert_eq!({ pool_state.token_1_program }, v0.token_1_program);
+        assert_eq!({ pool_state.observation_key }, v0.observation_key);
+        assert_eq!(
+            (
+                pool_state.auth_bump,
+                pool_state.status,
+                pool_state.mint_0_decimals,
+                pool_state.mint_1_decimals
+            ),
+            (253, 1, 6, 9)
+        );
+        assert_eq!({ pool_state.lp_supply }, 1_000);
+        assert_eq!(
+            (
+                pool_state.protocol_fees_token_0,
+                pool_state.protocol_fees_token_1,
+                pool_state.fund_fees_token_0,
+                pool_state.fund_fees_token_1
+            ),
+            (11, 12, 13, 14)
+        );
+        assert_eq!({ pool_state.open_time }, v0.open_time);
+
+        // the fields added since read the zeroed padding: no presale, statistics and
+        // buybacks from zero, no candles
+        assert_eq!(
+            ({ pool_state.presale_open_time }, { pool_state.presale_merkle_root }),
+            (0, [0u8; 32])
+        );
+        assert_eq!(
+            (
+                pool_state.buy_volume_token_1,
+                pool_state.sell_volume_token_1,
+                pool_state.trade_count,
+                pool_state.unique_buyer_count,
+                pool_state.last_buyback_time
+            ),
+            (0, 0, 0, 0, 0)
+        );
+        assert_eq!({ pool_state.ath_price_x32 }, 0);
+        assert_eq!({ pool_state.buyback_enabled }, 0);
+        assert_eq!({ pool_state.candle_key }, Pubkey::default());
+
+        // already up to date
+        assert!(!pool_state.migrate().unwrap());
+        pool_state.version = POOL_STATE_VERSION + 1;
+        assert!(pool_state.migrate().is_err());
+    }
+
+    #[test]
+    fn record_swap_statistics() {
+        let mut pool_state = PoolState::default();
+        pool_state.record_swap(true, 1_000, 100, true);
+        pool_state.record_swap(true, 500, 150, false);
+        pool_state.record_swap(false, 700, 120, false);
+        pool_state.record_swap(true, 200, 130, true);
+
+        assert_eq!({ pool_state.buy_volume_token_1 }, 1_700);
+        assert_eq!({ pool_state.sell_volume_token_1 }, 700);
+        assert_eq!({ pool_state.trade_count }, 4);
+        assert_eq!({ pool_state.unique_buyer_count }, 2);
+        assert_eq!({ pool_state.ath_price_x32 }, 150);
+    }
+
+    #[test]
+    fn buyback_rate_limit() {
+        let mut pool_state = PoolState::default();
+        assert!(pool_state.is_buyback_allowed(1_000));
+
+        pool_state.last_buyback_time = 1_000;
+        assert!(!pool_state.is_buyback_allowed(1_000));
+        assert!(!pool_state.is_buyback_allowed(1_000 + BUYBACK_MIN_INTERVAL - 1));
+        assert!(pool_state.is_buyback_allowed(1_000 + BUYBACK_MIN_INTERVAL));
+    }
+
+    #[test]
+    fn presale_window() {
+        let mut pool_state = PoolState {
+            presale_open_time: 100,
+            open_time: 200,
+            ..Default::default()
+        };
+        assert!(!pool_state.is_presale_active(150));
+
+        pool_state.presale_merkle_root = [1u8; 32];
+        assert!(!pool_state.is_presale_active(100));
+        assert!(pool_state.is_presale_active(101));
+        assert!(pool_state.is_presale_active(200));
+        assert!(!pool_state.is_presale_active(201));
+    }
 
     mod pool_status_test {
         use super::*;
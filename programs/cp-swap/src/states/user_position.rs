@@ -0,0 +1,101 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Seed to derive account address and signature
+pub const USER_POSITION_SEED: &str = "user_position";
+
+/// Tracks the token_0 a wallet has traded on a pool
+#[account]
+#[derive(Default, Debug)]
+pub struct UserPosition {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the position belongs to
+    pub pool_id: Pubkey,
+    /// The wallet owning the position
+    pub owner: Pubkey,
+    /// Total token_0 the owner bought from the pool
+    pub token_0_bought: u64,
+    /// Total token_0 the owner sold to the pool
+    pub token_0_sold: u64,
+    /// Total token_1 the owner spent in the presale
+    pub presale_amount_in: u64,
+    /// padding for future updates
+    pub padding: [u64; 7],
+}
+
+impl UserPosition {
+    pub const LEN: usize = 8 // discriminator
+      + 1 // u8
+      + 32 * 2 // Pubkey
+      + 8 * 3 // u64
+      + 8 * 7 // u64
+      ;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, owner: Pubkey) {
+        if self.pool_id != Pubkey::default() {
+            return;
+        }
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.owner = owner;
+    }
+
+    pub fn record_buy(&mut self, token_0_amount: u64) {
+        self.token_0_bought = self.token_0_bought.checked_add(token_0_amount).unwrap();
+    }
+
+    /// Record token_1 spent in the presale, failing once it exceeds the whitelisted `allocation`
+    pub fn record_presale_buy(&mut self, amount_in: u64, allocation: u64) -> Result<()> {
+        let presale_amount_in = self.presale_amount_in.checked_add(amount_in).unwrap();
+        require_gte!(
+            allocation,
+            presale_amount_in,
+            ErrorCode::ExceededPresaleAllocation
+        );
+        self.presale_amount_in = presale_amount_in;
+        Ok(())
+    }
+
+    pub fn record_sell(&mut self, token_0_amount: u64) {
+        self.token_0_sold = self.token_0_sold.checked_add(token_0_amount).unwrap();
+    }
+}
+
+#[cfg(test)]
+pub mod user_position_test {
+    use super::*;
+
+    #[test]
+    fn record_trades() {
+        let mut position = UserPosition::default();
+        position.initialize(255, Pubkey::new_unique(), Pubkey::new_unique());
+        position.record_buy(1_000);
+        position.record_sell(400);
+        position.record_buy(100);
+        // sales never give back room under the max buy cap
+        assert_eq!(position.token_0_bought, 1_100);
+        assert_eq!(position.token_0_sold, 400);
+    }
+
+    #[test]
+    fn presale_allocation() {
+        let mut position = UserPosition::default();
+        position.record_presale_buy(600, 1_000).unwrap();
+        position.record_presale_buy(400, 1_000).unwrap();
+        assert!(position.record_presale_buy(1, 1_000).is_err());
+        assert_eq!(position.presale_amount_in, 1_000);
+    }
+
+    #[test]
+    fn initialize_only_once() {
+        let pool_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut position = UserPosition::default();
+        position.initialize(254, pool_id, owner);
+        position.initialize(1, Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(position.bump, 254);
+        assert_eq!(position.pool_id, pool_id);
+        assert_eq!(position.owner, owner);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Seed to derive account address and signature
+pub const VESTING_SEED: &str = "vesting";
+pub const VESTING_VAULT_SEED: &str = "vesting_vault";
+
+/// When the locked tokens are released, in unix timestamps
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct VestingSchedule {
+    /// The timestamp the linear release starts from
+    pub start_time: u64,
+    /// Nothing can be claimed before this timestamp
+    pub cliff_time: u64,
+    /// Everything can be claimed from this timestamp
+    pub end_time: u64,
+}
+
+impl VestingSchedule {
+    /// Checks the timestamps are in order and that something stays locked after `block_timestamp`,
+    /// a schedule ending by then would release everything at once
+    pub fn validate(&self, block_timestamp: u64) -> Result<()> {
+        require!(
+            self.start_time <= self.cliff_time
+                && self.cliff_time <= self.end_time
+                && self.start_time < self.end_time,
+            ErrorCode::InvalidVestingSchedule
+        );
+        require_gt!(self.end_time, block_timestamp, ErrorCode::InvalidVestingSchedule);
+        Ok(())
+    }
+}
+
+/// token_0 a pool creator locked in escrow, released on a linear schedule after a cliff.
+/// A cliff at `end_time` releases everything at once
+#[account]
+#[derive(Default, Debug)]
+pub struct VestingState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool of the locked token_0
+    pub pool_id: Pubkey,
+    /// The wallet that locked and can claim the tokens
+    pub beneficiary: Pubkey,
+    /// The escrow token account holding the locked tokens
+    pub vault: Pubkey,
+    /// The token_0 locked
+    pub total_amount: u64,
+    /// The token_0 already claimed
+    pub claimed_amount: u64,
+    /// The timestamp the linear release starts from
+    pub start_time: u64,
+    /// Nothing can be claimed before this timestamp
+    pub cliff_time: u64,
+    /// Everything can be claimed from this timestamp
+    pub end_time: u64,
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl VestingState {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 5 + 8 * 8;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        pool_id: Pubkey,
+        beneficiary: Pubkey,
+        vault: Pubkey,
+        total_amount: u64,
+        schedule: VestingSchedule,
+    ) -> Result<()> {
+        let VestingSchedule {
+            start_time,
+            cliff_time,
+            end_time,
+        } = schedule;
+        require_gt!(total_amount, 0, ErrorCode::InvalidVestingSchedule);
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.beneficiary = beneficiary;
+        self.vault = vault;
+        self.total_amount = total_amount;
+        self.claimed_amount = 0;
+        self.start_time = start_time;
+        self.cliff_time = cliff_time;
+        self.end_time = end_time;
+        self.padding = [0u64; 8];
+        Ok(())
+    }
+
+    /// The token_0 released at `block_timestamp`, claimed or not
+    pub fn vested_amount(&self, block_timestamp: u64) -> u64 {
+        if block_timestamp < self.cliff_time {
+            return 0;
+        }
+        if block_timestamp >= self.end_time {
+            return self.total_amount;
+        }
+        let vested = u128::from(self.total_amount)
+            .checked_mul(u128::from(block_timestamp - self.start_time))
+            .unwrap()
+            .checked_div(u128::from(self.end_time - self.start_time))
+            .unwrap();
+        u64::try_from(vested).unwrap()
+    }
+
+    /// The token_0 the beneficiary can claim at `block_timestamp`
+    pub fn claimable_amount(&self, block_timestamp: u64) -> u64 {
+        self.vested_amount(block_timestamp)
+            .saturating_sub(self.claimed_amount)
+    }
+}
+
+#[cfg(test)]
+pub mod vesting_test {
+    use super::*;
+
+    fn vesting_state(start_time: u64, cliff_time: u64, end_time: u64) -> VestingState {
+        let mut vesting_state = VestingState::default();
+        vesting_state
+            .initialize(
+                255,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                1_000_000,
+                VestingSchedule {
+                    start_time,
+                    cliff_time,
+                    end_time,
+                },
+            )
+            .unwrap();
+        vesting_state
+    }
+
+    #[test]
+    fn vesting_len() {
+        assert_eq!(
+            VestingState::LEN,
+            8 + VestingState::default().try_to_vec().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn invalid_schedule() {
+        let validate = |start_time, cliff_time, end_time, block_timestamp| {
+            VestingSchedule {
+                start_time,
+                cliff_time,
+                end_time,
+            }
+            .validate(block_timestamp)
+        };
+        assert!(validate(100, 50, 200, 0).is_err());
+        assert!(validate(100, 300, 200, 0).is_err());
+        assert!(validate(100, 100, 100, 0).is_err());
+        assert!(validate(100, 100, 200, 0).is_ok());
+        // nothing would be locked from now on
+        assert!(validate(100, 100, 200, 200).is_err());
+        assert!(validate(100, 100, 200, 300).is_err());
+        // a schedule that started in the past still locks until its end
+        assert!(validate(100, 100, 200, 199).is_ok());
+
+        let schedule = VestingSchedule {
+            start_time: 100,
+            cliff_time: 100,
+            end_time: 200,
+        };
+        let key = Pubkey::new_unique();
+        let mut vesting_state = VestingState::default();
+        assert!(vesting_state
+            .initialize(255, key, key, key, 0, schedule)
+            .is_err());
+        assert!(vesting_state
+            .initialize(255, key, key, key, 1, schedule)
+            .is_ok());
+    }
+
+    #[test]
+    fn linear_schedule_with_cliff() {
+        let mut vesting_state = vesting_state(1_000, 1_250, 2_000);
+        assert_eq!(vesting_state.claimable_amount(999), 0);
+        assert_eq!(vesting_state.claimable_amount(1_249), 0);
+        // the cliff releases what vested since the start
+        assert_eq!(vesting_state.claimable_amount(1_250), 250_000);
+        assert_eq!(vesting_state.claimable_amount(1_500), 500_000);
+
+        vesting_state.claimed_amount = 500_000;
+        assert_eq!(vesting_state.claimable_amount(1_500), 0);
+        assert_eq!(vesting_state.claimable_amount(1_750), 250_000);
+        assert_eq!(vesting_state.claimable_amount(2_000), 500_000);
+        assert_eq!(vesting_state.claimable_amount(u64::MAX), 500_000);
+    }
+
+    #[test]
+    fn cliff_schedule() {
+        let vesting_state = vesting_state(1_000, 2_000, 2_000);
+        assert_eq!(vesting_state.claimable_amount(1_999), 0);
+        assert_eq!(vesting_state.claimable_amount(2_000), 1_000_000);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Fails once `block_timestamp` is past the `deadline` of a swap, a swap without deadline never expires
+pub fn check_deadline(deadline: Option<u64>, block_timestamp: u64) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require_gte!(deadline, block_timestamp, ErrorCode::DeadlineExceeded);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadline() {
+        let now = 1_700_000_000;
+        assert!(check_deadline(None, now).is_ok());
+        assert!(check_deadline(Some(now + 30), now).is_ok());
+        // the deadline second itself is still valid
+        assert!(check_deadline(Some(now), now).is_ok());
+        assert_eq!(
+            check_deadline(Some(now - 1), now).unwrap_err(),
+            ErrorCode::DeadlineExceeded.into()
+        );
+    }
+}
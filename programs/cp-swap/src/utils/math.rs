@@ -1,6 +1,7 @@
 ///! 128 and 256 bit numbers
 ///! U128 is more efficient that u128
 ///! https://github.com/solana-labs/solana/issues/19549
+use crate::error::ErrorCode;
 use uint::construct_uint;
 construct_uint! {
     pub struct U128(2);
@@ -46,10 +47,40 @@ impl CheckedCeilDiv for u128 {
     }
 }
 
-pub fn to_decimals(amount: u64, decimals: u32) -> u64 {
-    amount * 10u64.pow(decimals)
+/// `amount` whole tokens in base units, errors when it does not fit a u64
+pub fn to_decimals(amount: u64, decimals: u32) -> anchor_lang::Result<u64> {
+    10u64
+        .checked_pow(decimals)
+        .and_then(|scale| amount.checked_mul(scale))
+        .ok_or_else(|| ErrorCode::MathOverflow.into())
 }
 
-pub fn from_decimals(amount: u64, decimals: u32) -> u64 {
-    amount / 10u64.pow(decimals)
+/// `amount` base units in whole tokens, rounded down
+pub fn from_decimals(amount: u64, decimals: u32) -> anchor_lang::Result<u64> {
+    10u64
+        .checked_pow(decimals)
+        .map(|scale| amount / scale)
+        .ok_or_else(|| ErrorCode::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_conversion() {
+        assert_eq!(
+            to_decimals(1_000_000_000, 9).unwrap(),
+            1_000_000_000_000_000_000
+        );
+        assert_eq!(from_decimals(1_999_999_999, 9).unwrap(), 1);
+        assert_eq!(to_decimals(5, 0).unwrap(), 5);
+    }
+
+    #[test]
+    fn decimals_overflow() {
+        assert!(to_decimals(1_000_000_000, 11).is_err());
+        assert!(to_decimals(1, 20).is_err());
+        assert!(from_decimals(1, 20).is_err());
+    }
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Leaf of the presale Merkle tree, keccak256(owner || allocation as little endian u64)
+pub fn presale_leaf(owner: &Pubkey, allocation: u64) -> [u8; 32] {
+    keccak::hashv(&[owner.as_ref(), &allocation.to_le_bytes()]).0
+}
+
+/// Verify a Merkle proof built by hashing each pair of nodes in sorted order
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+    computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    #[test]
+    fn verify_presale_proof() {
+        let owners: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = owners
+            .iter()
+            .enumerate()
+            .map(|(i, owner)| presale_leaf(owner, (i as u64 + 1) * 1_000))
+            .collect();
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[3]);
+        let root = hash_pair(left, right);
+
+        assert!(verify_merkle_proof(&[leaves[1], right], root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[3], left], root, leaves[2]));
+
+        // wrong allocation
+        let leaf = presale_leaf(&owners[0], 2_000);
+        assert!(!verify_merkle_proof(&[leaves[1], right], root, leaf));
+        // wrong owner
+        let leaf = presale_leaf(&Pubkey::new_unique(), 1_000);
+        assert!(!verify_merkle_proof(&[leaves[1], right], root, leaf));
+        // wrong proof
+        assert!(!verify_merkle_proof(&[leaves[2], right], root, leaves[0]));
+        assert!(!verify_merkle_proof(&[], root, leaves[0]));
+    }
+}
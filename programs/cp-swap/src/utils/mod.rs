@@ -6,3 +6,9 @@ pub use token::*;
 
 pub mod account;
 pub use account::*;
+
+pub mod merkle;
+pub use merkle::*;
+
+pub mod deadline;
+pub use deadline::*;
@@ -281,3 +281,120 @@ pub fn create_system_account<'a>(
     )?;
     Ok(())
 }
+
+/// The max decimals of a created mint, so the whole supply fits a u64 in base units
+pub const MAX_MINT_DECIMALS: u8 = 9;
+
+/// Checks the token metadata against the Metaplex length limits
+pub fn validate_token_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    use anchor_spl::metadata::mpl_token_metadata::{
+        MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+    };
+    require!(
+        !name.is_empty()
+            && name.len() <= MAX_NAME_LENGTH
+            && !symbol.is_empty()
+            && symbol.len() <= MAX_SYMBOL_LENGTH
+            && uri.len() <= MAX_URI_LENGTH,
+        ErrorCode::InvalidMetadata
+    );
+    Ok(())
+}
+
+/// The wallet that created a mint with create_mint, recorded as the first creator of its metadata
+///
+/// # Arguments
+///
+/// * `metadata` - The Metaplex metadata of the mint
+/// * `update_authority` - The program authority, update authority of the metadata create_mint creates
+///
+pub fn mint_creator(metadata: &AccountInfo, update_authority: &Pubkey) -> Result<Pubkey> {
+    use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+    let metadata = Metadata::from_bytes(&metadata.try_borrow_data()?)
+        .map_err(|_| ErrorCode::InvalidMetadata)?;
+    require_keys_eq!(
+        metadata.update_authority,
+        *update_authority,
+        ErrorCode::InvalidMetadata
+    );
+    metadata
+        .creators
+        .and_then(|creators| creators.first().map(|creator| creator.address))
+        .ok_or_else(|| error!(ErrorCode::InvalidMetadata))
+}
+
+/// The lamports that can be paid out of a native token_1 vault: at most the accrued
+/// fees, and never so much that the vault falls below rent exemption
+///
+/// # Arguments
+///
+/// * `amount_requested` - The maximum amount asked for
+/// * `accrued_fees` - The fees accrued in the vault
+/// * `vault_lamports` - The current lamports of the vault
+/// * `rent_exempt_minimum` - The rent exempt minimum balance of the vault
+///
+pub fn native_vault_collectable_amount(
+    amount_requested: u64,
+    accrued_fees: u64,
+    vault_lamports: u64,
+    rent_exempt_minimum: u64,
+) -> u64 {
+    amount_requested
+        .min(accrued_fees)
+        .min(vault_lamports.saturating_sub(rent_exempt_minimum))
+}
+
+#[cfg(test)]
+pub mod token_test {
+    use super::*;
+
+    #[test]
+    fn token_metadata_limits() {
+        assert!(validate_token_metadata("Token", "TKN", "https://example.com").is_ok());
+        assert!(
+            validate_token_metadata(&"n".repeat(32), &"s".repeat(10), &"u".repeat(200)).is_ok()
+        );
+        assert!(validate_token_metadata("", "TKN", "").is_err());
+        assert!(validate_token_metadata("Token", "", "").is_err());
+        assert!(validate_token_metadata(&"n".repeat(33), "TKN", "").is_err());
+        assert!(validate_token_metadata("Token", &"s".repeat(11), "").is_err());
+        assert!(validate_token_metadata("Token", "TKN", &"u".repeat(201)).is_err());
+    }
+
+    #[test]
+    fn collectable_amount_capped_by_fees() {
+        assert_eq!(
+            native_vault_collectable_amount(u64::MAX, 5_000, 10_000_000, 890_880),
+            5_000
+        );
+        assert_eq!(
+            native_vault_collectable_amount(1_000, 5_000, 10_000_000, 890_880),
+            1_000
+        );
+    }
+
+    #[test]
+    fn collectable_amount_keeps_vault_rent_exempt() {
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+        let vault_lamports = rent_exempt_minimum + 3_000;
+        let amount =
+            native_vault_collectable_amount(u64::MAX, 5_000, vault_lamports, rent_exempt_minimum);
+        assert_eq!(amount, 3_000);
+        assert_eq!(vault_lamports - amount, rent_exempt_minimum);
+
+        // nothing to pay out of a vault at or below the minimum
+        assert_eq!(
+            native_vault_collectable_amount(
+                u64::MAX,
+                5_000,
+                rent_exempt_minimum,
+                rent_exempt_minimum
+            ),
+            0
+        );
+        assert_eq!(
+            native_vault_collectable_amount(u64::MAX, 5_000, 0, rent_exempt_minimum),
+            0
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Candles of a pool: once `create_candle_state` ran, every swap and buyback must pass them
+//! and record them, and `get_candles` returns them.
+
+mod common;
+
+use anchor_lang::prelude::*;
+
+use booster_swap::{
+    curve::TradeDirection, error::ErrorCode, instructions::SwapLeg, states::CandleResolution,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair};
+
+#[tokio::test]
+async fn swaps_record_candles_once_created() {
+    let mut pool = setup_pool(1).await;
+    // a pool without candles trades without the account
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+
+    pool.create_candle_state().await.unwrap();
+    assert_eq!(
+        { pool.pool_state().await.candle_key },
+        pool.candle_state.unwrap()
+    );
+    pool.swap_base_input(0, TradeDirection::OneForZero, 2 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let bought = pool.token_balance(pool.users[0].token_0_account).await;
+    pool.swap_base_output(0, TradeDirection::ZeroForOne, bought, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    for resolution in CandleResolution::ALL {
+        let candles = pool.get_candles(resolution, 10).await.unwrap();
+        assert!(!candles.is_empty());
+        let volume_token_1: u64 = candles.iter().map(|candle| candle.volume_token_1).sum();
+        // only the trades made since the candles exist, net of fees
+        assert!(volume_token_1 > 2 * LAMPORTS_PER_SOL);
+        assert!(volume_token_1 < 3 * LAMPORTS_PER_SOL + LAMPORTS_PER_SOL / 10);
+        let latest = candles.last().unwrap();
+        assert!(latest.low_price_x32 <= latest.close_price_x32);
+        assert!(latest.close_price_x32 <= latest.high_price_x32);
+    }
+
+    for count in [0, 11] {
+        assert_eq!(
+            custom_error(
+                pool.get_candles(CandleResolution::OneMinute, count)
+                    .await
+                    .map(|_| 0)
+            ),
+            ErrorCode::InvalidInput as u32 + ERROR_CODE_OFFSET
+        );
+    }
+}
+
+#[tokio::test]
+async fn candles_created_by_the_pool_creator_only() {
+    let mut pool = setup_pool(1).await;
+    let user = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    let instruction = pool.create_candle_state_ix(pool.users[0].pubkey());
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&user]).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+    // swaps still go through without the candle account
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    assert_eq!({ pool.pool_state().await.candle_key }, Pubkey::default());
+}
+
+#[tokio::test]
+async fn swaps_without_candles_rejected_once_created() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 10 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.create_candle_state().await.unwrap();
+    pool.set_buyback(true).await.unwrap();
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let candles = pool.get_candles(CandleResolution::OneHour, 1).await.unwrap();
+
+    // no swap, batch leg or buyback may leave the candle account out
+    let candle_state = pool.candle_state.take();
+    assert_eq!(
+        custom_error(
+            pool.swap_base_input(0, TradeDirection::OneForZero, 2 * LAMPORTS_PER_SOL, 0)
+                .await
+        ),
+        ErrorCode::CandleStateMissing as u32 + ERROR_CODE_OFFSET
+    );
+    assert_eq!(
+        custom_error(
+            pool.swap_base_output(0, TradeDirection::ZeroForOne, u64::MAX, 1_000)
+                .await
+        ),
+        ErrorCode::CandleStateMissing as u32 + ERROR_CODE_OFFSET
+    );
+    let legs = [SwapLeg {
+        trade_direction: TradeDirection::OneForZero as u8,
+        amount_in: LAMPORTS_PER_SOL,
+        minimum_amount_out: 0,
+    }];
+    let instruction = pool.swap_batch_ix(&pool.users[0], &legs, false);
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&signer]).await),
+        ErrorCode::CandleStateMissing as u32 + ERROR_CODE_OFFSET
+    );
+    let fee_owner = Keypair::from_bytes(&pool.fee_owner.keypair.to_bytes()).unwrap();
+    let instruction = pool.buyback_and_burn_ix(pool.fee_owner.pubkey());
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&fee_owner]).await),
+        ErrorCode::CandleStateMissing as u32 + ERROR_CODE_OFFSET
+    );
+
+    pool.candle_state = candle_state;
+    assert_eq!(
+        pool.get_candles(CandleResolution::OneHour, 1).await.unwrap(),
+        candles
+    );
+
+    // no trade is missing, the next candle opens at the previous close
+    pool.warp_clock(60 * 60).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let candles = pool.get_candles(CandleResolution::OneHour, 2).await.unwrap();
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[1].open_price_x32, candles[0].close_price_x32);
+    assert!(candles[1].close_price_x32 > candles[1].open_price_x32);
+}
+
+#[tokio::test]
+async fn route_and_buyback_record_candles() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 10 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let buy = pool.launch_pool().await;
+    let buy_token_0_account = pool.create_token_0_account(0, &buy).await;
+    let previous = pool.select_pool(buy);
+    pool.create_candle_state().await.unwrap();
+    let buy = pool.select_pool(previous);
+
+    // the route passes the candles of the pool it buys on only
+    let amount_in = pool.token_balance(pool.users[0].token_0_account).await / 2;
+    let instruction = pool.swap_route_ix(&pool.users[0], &buy, buy_token_0_account, amount_in, 1);
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    pool.process(&[instruction], &[&signer]).await.unwrap();
+    let previous = pool.select_pool(buy);
+    let candles = pool.get_candles(CandleResolution::OneMinute, 1).await.unwrap();
+    assert_eq!(candles.len(), 1);
+    assert!(candles[0].volume_token_0 > 0);
+
+    // the buyback moves the price like a buy
+    pool.set_buyback(true).await.unwrap();
+    let protocol_fees_token_1 = { pool.pool_state().await.protocol_fees_token_1 };
+    let fee_owner = Keypair::from_bytes(&pool.fee_owner.keypair.to_bytes()).unwrap();
+    let instruction = pool.buyback_and_burn_ix(pool.fee_owner.pubkey());
+    pool.process(&[instruction], &[&fee_owner]).await.unwrap();
+    let after = pool.get_candles(CandleResolution::OneMinute, 1).await.unwrap();
+    assert_eq!(
+        after[0].volume_token_1,
+        candles[0].volume_token_1 + protocol_fees_token_1
+    );
+    assert!(after[0].close_price_x32 > candles[0].close_price_x32);
+    pool.select_pool(previous);
+}
@@ -0,0 +1,1048 @@
+//! Shared fixtures of the integration tests: a pool created on a `ProgramTest` bank by
+//! `create_amm_config`, `create_mint` and `initialize`, as the admin would.
+//!
+//! `cargo test` runs the program through its native entrypoint, `cargo test-sbf` runs the
+//! SBF build so the compute units are metered.
+//!
+//! The token metadata program is not bundled with `ProgramTest`. `cargo test` runs the native
+//! stand-in of `token_metadata`, so no test needs a mainnet clone. `cargo test-sbf` loads
+//! `tests/fixtures/mpl_token_metadata.so`, dumped once with
+//! `solana program dump metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so`.
+#![allow(dead_code)]
+
+mod token_metadata;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack},
+    system_program, InstructionData,
+};
+use anchor_spl::{
+    metadata::mpl_token_metadata::{self, accounts::Metadata},
+    token::spl_token,
+};
+use booster_swap::{curve::TradeDirection, states::*};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, instruction::InstructionError, message::Message,
+    native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer, transaction::Transaction,
+    transaction::TransactionError,
+};
+
+pub const TOKEN_0_DECIMALS: u8 = 6;
+pub const TOKEN_0_NAME: &str = "Booster";
+pub const TOKEN_0_SYMBOL: &str = "BOOST";
+pub const TOKEN_0_URI: &str = "https://example.com/booster.json";
+pub const AMM_CONFIG_INDEX: u16 = 0;
+pub const TRADE_FEE_RATE: u64 = 10_000;
+pub const PROTOCOL_FEE_RATE: u64 = 120_000;
+pub const FUND_FEE_RATE: u64 = 40_000;
+/// The lamports each user of the fixture starts with
+pub const USER_LAMPORTS: u64 = 1_000 * LAMPORTS_PER_SOL;
+pub const TOKEN_METADATA_PROGRAM_FIXTURE: &str = "tests/fixtures/mpl_token_metadata.so";
+
+fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    // the anchor entrypoint ties the accounts slice to the lifetime of the accounts
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    booster_swap::entry(program_id, accounts, data)
+}
+
+/// Whether the tests run the SBF build, as `ProgramTest` decides it
+pub fn is_sbf() -> bool {
+    std::env::var("BPF_OUT_DIR").is_ok() || std::env::var("SBF_OUT_DIR").is_ok()
+}
+
+/// The SBF build of the token metadata program, if it was dumped to the fixtures
+pub fn token_metadata_program() -> Option<Vec<u8>> {
+    std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(TOKEN_METADATA_PROGRAM_FIXTURE),
+    )
+    .ok()
+}
+
+/// A bank with the program and the token metadata program
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "booster_swap",
+        booster_swap::id(),
+        processor!(process_instruction),
+    );
+    // the SBF build loads tests/fixtures/mpl_token_metadata.so, the native one the stand-in
+    program_test.add_program(
+        "mpl_token_metadata",
+        mpl_token_metadata::ID,
+        processor!(token_metadata::process_instruction),
+    );
+    program_test
+}
+
+fn program_account(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Adds an empty token_0 account of `owner`
+pub fn add_token_account(program_test: &mut ProgramTest, mint: Pubkey, owner: Pubkey) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(address, program_account(data, spl_token::id()));
+    address
+}
+
+fn add_wallet(program_test: &mut ProgramTest, address: Pubkey) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: USER_LAMPORTS,
+            ..Account::default()
+        },
+    );
+}
+
+/// Adds a wallet holding `USER_LAMPORTS` and its token_0 account
+pub fn add_user(program_test: &mut ProgramTest, mint: Pubkey) -> User {
+    let keypair = Keypair::new();
+    add_wallet(program_test, keypair.pubkey());
+    let token_0_account = add_token_account(program_test, mint, keypair.pubkey());
+    User {
+        keypair,
+        token_0_account,
+    }
+}
+
+pub struct User {
+    pub keypair: Keypair,
+    pub token_0_account: Pubkey,
+}
+
+impl User {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+/// The accounts of one pool of the amm config, derived from its token_0 mint
+#[derive(Clone, Copy, Debug)]
+pub struct PoolKeys {
+    pub pool_state: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub metadata: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub observation_state: Pubkey,
+    /// The candle account, once `create_candle_state` created it
+    pub candle_state: Option<Pubkey>,
+}
+
+impl PoolKeys {
+    pub fn new(amm_config: &Pubkey, token_0_mint: Pubkey) -> Self {
+        let program_id = booster_swap::id();
+        let (pool_state, _) = Pubkey::find_program_address(
+            &[
+                POOL_SEED.as_bytes(),
+                amm_config.as_ref(),
+                token_0_mint.as_ref(),
+            ],
+            &program_id,
+        );
+        let (token_0_vault, _) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_state.as_ref(),
+                token_0_mint.as_ref(),
+            ],
+            &program_id,
+        );
+        let (token_1_vault, _) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_state.as_ref(),
+                system_program::ID.as_ref(),
+            ],
+            &program_id,
+        );
+        let (observation_state, _) = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_state.as_ref()],
+            &program_id,
+        );
+        Self {
+            pool_state,
+            token_0_mint,
+            metadata: Metadata::find_pda(&token_0_mint).0,
+            token_0_vault,
+            token_1_vault,
+            observation_state,
+            candle_state: None,
+        }
+    }
+}
+
+/// An initialized pool and the wallets trading on it
+pub struct PoolFixture {
+    pub context: ProgramTestContext,
+    /// Owner of the protocol and fund fees of the amm config
+    pub fee_owner: User,
+    pub users: Vec<User>,
+    /// token_0 account of the admin, which created the pool
+    pub admin_token_0_account: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub pool_state: Pubkey,
+    pub token_0_mint: Pubkey,
+    /// The Metaplex metadata of token_0
+    pub metadata: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub observation_state: Pubkey,
+    /// The candle account, once `create_candle_state` created it
+    pub candle_state: Option<Pubkey>,
+    /// Compute units consumed by `initialize`
+    pub initialize_compute_units: u64,
+}
+
+/// Starts a bank with `user_count` funded wallets and lets the admin create the amm config and token_0,
+/// the pool is not initialized yet
+pub async fn setup_mint(user_count: usize) -> PoolFixture {
+    let mut program_test = program_test();
+    let program_id = booster_swap::id();
+    let admin = booster_swap::admin::id();
+    let (authority, _) =
+        Pubkey::find_program_address(&[booster_swap::AUTH_SEED.as_bytes()], &program_id);
+    let mint_keypair = Keypair::new();
+    let token_0_mint = mint_keypair.pubkey();
+    let (amm_config, _) = Pubkey::find_program_address(
+        &[AMM_CONFIG_SEED.as_bytes(), &AMM_CONFIG_INDEX.to_be_bytes()],
+        &program_id,
+    );
+    add_wallet(&mut program_test, admin);
+    let admin_token_0_account = add_token_account(&mut program_test, token_0_mint, admin);
+    let fee_owner = add_user(&mut program_test, token_0_mint);
+    let users = (0..user_count)
+        .map(|_| add_user(&mut program_test, token_0_mint))
+        .collect();
+    program_test.add_account(
+        booster_swap::create_pool_fee_receiver::id(),
+        program_account(vec![], system_program::ID),
+    );
+
+    let keys = PoolKeys::new(&amm_config, token_0_mint);
+    let mut pool = PoolFixture {
+        context: program_test.start_with_context().await,
+        fee_owner,
+        users,
+        admin_token_0_account,
+        amm_config,
+        authority,
+        pool_state: keys.pool_state,
+        token_0_mint,
+        metadata: keys.metadata,
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        observation_state: keys.observation_state,
+        candle_state: None,
+        initialize_compute_units: 0,
+    };
+    let create_amm_config = Instruction {
+        program_id,
+        accounts: booster_swap::accounts::CreateAmmConfig {
+            owner: admin,
+            amm_config,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: booster_swap::instruction::CreateAmmConfig {
+            index: AMM_CONFIG_INDEX,
+            trade_from_zero_to_one_fee_rate: TRADE_FEE_RATE,
+            trade_from_one_to_zero_fee_rate: TRADE_FEE_RATE,
+            protocol_fee_rate: PROTOCOL_FEE_RATE,
+            fund_fee_rate: FUND_FEE_RATE,
+            create_pool_fee: 0,
+        }
+        .data(),
+    };
+    // the protocol and fund fees go to the fee owner
+    let fee_owner = pool.fee_owner.pubkey();
+    let update_amm_config = |param: u8| {
+        let mut accounts = booster_swap::accounts::UpdateAmmConfig {
+            owner: admin,
+            amm_config,
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new_readonly(fee_owner, false));
+        Instruction {
+            program_id,
+            accounts,
+            data: booster_swap::instruction::UpdateAmmConfig { param, value: 0 }.data(),
+        }
+    };
+    let instructions = [
+        create_amm_config,
+        update_amm_config(4),
+        update_amm_config(5),
+    ];
+    pool.process(&instructions, &[]).await.unwrap();
+
+    let create_mint = pool.create_mint_ix(admin);
+    pool.process(&[create_mint], &[&mint_keypair])
+        .await
+        .unwrap();
+
+    pool
+}
+
+/// Starts a bank with `user_count` funded wallets, lets the admin create the amm config and token_0,
+/// then initialize a pool open right away
+pub async fn setup_pool(user_count: usize) -> PoolFixture {
+    let mut pool = setup_mint(user_count).await;
+    let initialize = pool.initialize_ix(booster_swap::admin::id(), None);
+    pool.initialize_compute_units = pool.process(&[initialize], &[]).await.unwrap();
+    pool
+}
+
+/// Sends `instructions` in one transaction paid by the bank payer,
+/// returns the compute units consumed.
+/// `process_transaction_with_metadata` does not verify signatures, so the admin
+/// is a signer of the instructions without its keypair
+pub async fn process_transaction(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<u64, BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let mut transaction =
+        Transaction::new_unsigned(Message::new(instructions, Some(&context.payer.pubkey())));
+    transaction.partial_sign(&all_signers, blockhash);
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await?;
+    result.result?;
+    Ok(result
+        .metadata
+        .map(|metadata| metadata.compute_units_consumed)
+        .unwrap_or_default())
+}
+
+/// The custom error code a failed transaction returned
+pub fn custom_error(result: std::result::Result<u64, BanksClientError>) -> u32 {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => code,
+        other => panic!("expected a custom program error, got {:?}", other),
+    }
+}
+
+impl PoolFixture {
+    /// Sends `instructions` in one transaction paid by the bank payer,
+    /// returns the compute units consumed
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<u64, BanksClientError> {
+        process_transaction(&mut self.context, instructions, signers).await
+    }
+
+    /// `initialize` of the pool by `creator`, open right away
+    pub fn initialize_ix(&self, creator: Pubkey, observation_num: Option<u16>) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::Initialize {
+                creator,
+                amm_config: self.amm_config,
+                authority: self.authority,
+                pool_state: self.pool_state,
+                token_0_mint: self.token_0_mint,
+                metadata: self.metadata,
+                token_0_vault: self.token_0_vault,
+                token_1_vault: self.token_1_vault,
+                create_pool_fee: booster_swap::create_pool_fee_receiver::id(),
+                observation_state: self.observation_state,
+                token_program: spl_token::id(),
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+                token_metadata_program: mpl_token_metadata::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::Initialize {
+                open_time: 0,
+                observation_num,
+                observation_update_duration: None,
+            }
+            .data(),
+        }
+    }
+
+    /// `create_mint` of token_0 by `creator`
+    pub fn create_mint_ix(&self, creator: Pubkey) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::CreateMint {
+                metadata: self.metadata,
+                mint: self.token_0_mint,
+                authority: self.authority,
+                creator,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: spl_token::id(),
+                token_metadata_program: mpl_token_metadata::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::CreateMint {
+                metadata: booster_swap::MintParams {
+                    name: TOKEN_0_NAME.to_string(),
+                    symbol: TOKEN_0_SYMBOL.to_string(),
+                    uri: TOKEN_0_URI.to_string(),
+                    decimals: TOKEN_0_DECIMALS,
+                },
+            }
+            .data(),
+        }
+    }
+
+    /// The accounts of the pool the helpers act on
+    pub fn keys(&self) -> PoolKeys {
+        PoolKeys {
+            pool_state: self.pool_state,
+            token_0_mint: self.token_0_mint,
+            metadata: self.metadata,
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            observation_state: self.observation_state,
+            candle_state: self.candle_state,
+        }
+    }
+
+    /// Makes the helpers act on the pool of `keys`, returns the pool they acted on
+    pub fn select_pool(&mut self, keys: PoolKeys) -> PoolKeys {
+        let previous = self.keys();
+        self.pool_state = keys.pool_state;
+        self.token_0_mint = keys.token_0_mint;
+        self.metadata = keys.metadata;
+        self.token_0_vault = keys.token_0_vault;
+        self.token_1_vault = keys.token_1_vault;
+        self.observation_state = keys.observation_state;
+        self.candle_state = keys.candle_state;
+        previous
+    }
+
+    /// Lets the admin create another token_0 and its pool on the same amm config,
+    /// the helpers keep acting on the current pool
+    pub async fn launch_pool(&mut self) -> PoolKeys {
+        let mint_keypair = Keypair::new();
+        let keys = PoolKeys::new(&self.amm_config, mint_keypair.pubkey());
+        let previous = self.select_pool(keys);
+        let create_mint = self.create_mint_ix(booster_swap::admin::id());
+        self.process(&[create_mint], &[&mint_keypair])
+            .await
+            .unwrap();
+        let initialize = self.initialize_ix(booster_swap::admin::id(), None);
+        self.process(&[initialize], &[]).await.unwrap();
+        self.select_pool(previous);
+        keys
+    }
+
+    /// Creates an empty account of the user at `user_index` for the token_0 of `keys`
+    pub async fn create_token_0_account(&mut self, user_index: usize, keys: &PoolKeys) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            anchor_lang::solana_program::system_instruction::create_account(
+                &self.context.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                &account.pubkey(),
+                &keys.token_0_mint,
+                &self.users[user_index].pubkey(),
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    pub fn swap_accounts(&self, user: &User) -> booster_swap::accounts::Swap {
+        self.swap_accounts_on(&self.keys(), user, user.token_0_account)
+    }
+
+    /// The `Swap` accounts of `user` on the pool of `keys`, trading from `token_0_account`
+    pub fn swap_accounts_on(
+        &self,
+        keys: &PoolKeys,
+        user: &User,
+        token_0_account: Pubkey,
+    ) -> booster_swap::accounts::Swap {
+        let (user_position, _) = Pubkey::find_program_address(
+            &[
+                USER_POSITION_SEED.as_bytes(),
+                keys.pool_state.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &booster_swap::id(),
+        );
+        booster_swap::accounts::Swap {
+            payer: user.pubkey(),
+            create_pool_fee: booster_swap::create_pool_fee_receiver::id(),
+            authority: self.authority,
+            amm_config: self.amm_config,
+            pool_state: keys.pool_state,
+            token_0_account,
+            token_1_account: user.pubkey(),
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_0_mint: keys.token_0_mint,
+            observation_state: keys.observation_state,
+            user_position,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+            candle_state: keys.candle_state,
+        }
+    }
+
+    /// `close_user_position` of `user` on the pool
+    pub fn close_user_position_ix(&self, user: &User) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::CloseUserPosition {
+                owner: user.pubkey(),
+                pool_state: self.pool_state,
+                amm_config: self.amm_config,
+                user_position: self.swap_accounts(user).user_position,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::CloseUserPosition {}.data(),
+        }
+    }
+
+    /// `swap_route` by `user`, selling on this pool and buying on the pool of `buy`
+    /// into `buy_token_0_account`
+    pub fn swap_route_ix(
+        &self,
+        user: &User,
+        buy: &PoolKeys,
+        buy_token_0_account: Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::SwapRoute {
+                sell: self.swap_accounts(user),
+                buy: self.swap_accounts_on(buy, user, buy_token_0_account),
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::SwapRoute {
+                amount_in,
+                minimum_amount_out,
+                deadline: None,
+            }
+            .data(),
+        }
+    }
+
+    pub fn swap_base_input_ix(
+        &self,
+        user: &User,
+        trade_direction: TradeDirection,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        deadline: Option<u64>,
+    ) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: self.swap_accounts(user).to_account_metas(None),
+            data: booster_swap::instruction::SwapBaseInput {
+                trade_direction: trade_direction as u8,
+                amount_in,
+                minimum_amount_out,
+                presale_proof: None,
+                deadline,
+            }
+            .data(),
+        }
+    }
+
+    pub fn swap_base_output_ix(
+        &self,
+        user: &User,
+        trade_direction: TradeDirection,
+        max_amount_in: u64,
+        amount_out: u64,
+        deadline: Option<u64>,
+    ) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: self.swap_accounts(user).to_account_metas(None),
+            data: booster_swap::instruction::SwapBaseOutput {
+                trade_direction: trade_direction as u8,
+                max_amount_in,
+                amount_out,
+                deadline,
+            }
+            .data(),
+        }
+    }
+
+    /// `swap_batch` of `legs` on this pool by `user`
+    pub fn swap_batch_ix(
+        &self,
+        user: &User,
+        legs: &[booster_swap::instructions::SwapLeg],
+        skip_failed_legs: bool,
+    ) -> Instruction {
+        let mut accounts = booster_swap::accounts::SwapBatch {
+            payer: user.pubkey(),
+        }
+        .to_account_metas(None);
+        for _ in legs {
+            accounts.extend(self.swap_accounts(user).to_account_metas(None));
+        }
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts,
+            data: booster_swap::instruction::SwapBatch {
+                legs: legs.to_vec(),
+                skip_failed_legs,
+                deadline: None,
+            }
+            .data(),
+        }
+    }
+
+    /// Runs `swap_base_input` for the user at `user_index`, returns the compute units consumed
+    pub async fn swap_base_input(
+        &mut self,
+        user_index: usize,
+        trade_direction: TradeDirection,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let user = &self.users[user_index];
+        let instruction =
+            self.swap_base_input_ix(user, trade_direction, amount_in, minimum_amount_out, None);
+        process_transaction(&mut self.context, &[instruction], &[&user.keypair]).await
+    }
+
+    /// Runs `initialize` signed by the user at `user_index` instead of the creator of the mint
+    pub async fn initialize_by(
+        &mut self,
+        user_index: usize,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let user = &self.users[user_index];
+        let instruction = self.initialize_ix(user.pubkey(), None);
+        process_transaction(&mut self.context, &[instruction], &[&user.keypair]).await
+    }
+
+    /// Runs `swap_base_output` for the user at `user_index`, returns the compute units consumed
+    pub async fn swap_base_output(
+        &mut self,
+        user_index: usize,
+        trade_direction: TradeDirection,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let user = &self.users[user_index];
+        let instruction =
+            self.swap_base_output_ix(user, trade_direction, max_amount_in, amount_out, None);
+        process_transaction(&mut self.context, &[instruction], &[&user.keypair]).await
+    }
+
+    /// Lets the admin set `param` of the amm config to `value`
+    pub async fn update_amm_config(
+        &mut self,
+        param: u8,
+        value: u64,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::UpdateAmmConfig {
+                owner: booster_swap::admin::id(),
+                amm_config: self.amm_config,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::UpdateAmmConfig { param, value }.data(),
+        };
+        self.process(&[instruction], &[]).await
+    }
+
+    /// Lets the admin set the status bits of the pool
+    pub async fn update_pool_status(
+        &mut self,
+        status: u8,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::UpdatePoolStatus {
+                authority: booster_swap::admin::id(),
+                pool_state: self.pool_state,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::UpdatePoolStatus { status }.data(),
+        };
+        self.process(&[instruction], &[]).await
+    }
+
+    /// Lets the admin upgrade the pool state to the current layout
+    pub async fn migrate_pool_state(&mut self) -> std::result::Result<u64, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::MigratePoolState {
+                authority: booster_swap::admin::id(),
+                pool_state: self.pool_state,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::MigratePoolState {}.data(),
+        };
+        self.process(&[instruction], &[]).await
+    }
+
+    /// Collects all the protocol fees to the fee owner
+    pub async fn collect_protocol_fee(&mut self) -> std::result::Result<u64, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::CollectProtocolFee {
+                owner: self.fee_owner.pubkey(),
+                authority: self.authority,
+                pool_state: self.pool_state,
+                amm_config: self.amm_config,
+                token_0_vault: self.token_0_vault,
+                token_1_vault: self.token_1_vault,
+                vault_0_mint: self.token_0_mint,
+                recipient_token_0_account: self.fee_owner.token_0_account,
+                recipient_token_1_account: self.fee_owner.pubkey(),
+                token_program: spl_token::id(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::CollectProtocolFee {
+                amount_0_requested: u64::MAX,
+                amount_1_requested: u64::MAX,
+            }
+            .data(),
+        };
+        process_transaction(
+            &mut self.context,
+            &[instruction],
+            &[&self.fee_owner.keypair],
+        )
+        .await
+    }
+
+    /// Collects the fund fees to `recipient_token_1_account`, up to the requested amounts
+    pub async fn collect_fund_fee(
+        &mut self,
+        recipient_token_1_account: Pubkey,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::CollectFundFee {
+                owner: self.fee_owner.pubkey(),
+                authority: self.authority,
+                pool_state: self.pool_state,
+                amm_config: self.amm_config,
+                token_0_vault: self.token_0_vault,
+                token_1_vault: self.token_1_vault,
+                vault_0_mint: self.token_0_mint,
+                recipient_token_0_account: self.fee_owner.token_0_account,
+                recipient_token_1_account,
+                token_program: spl_token::id(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::CollectFundFee {
+                amount_0_requested,
+                amount_1_requested,
+            }
+            .data(),
+        };
+        process_transaction(
+            &mut self.context,
+            &[instruction],
+            &[&self.fee_owner.keypair],
+        )
+        .await
+    }
+
+    /// `set_buyback` of the pool signed by `pool_creator`
+    pub fn set_buyback_ix(&self, pool_creator: Pubkey, enabled: bool) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::SetBuyback {
+                pool_creator,
+                pool_state: self.pool_state,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::SetBuyback { enabled }.data(),
+        }
+    }
+
+    /// Lets the admin, creator of the pool, opt it in or out of buybacks
+    pub async fn set_buyback(&mut self, enabled: bool) -> std::result::Result<u64, BanksClientError> {
+        let instruction = self.set_buyback_ix(booster_swap::admin::id(), enabled);
+        self.process(&[instruction], &[]).await
+    }
+
+    /// `buyback_and_burn` of all the token_1 protocol fees signed by `owner`
+    pub fn buyback_and_burn_ix(&self, owner: Pubkey) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::BuybackAndBurn {
+                owner,
+                authority: self.authority,
+                pool_state: self.pool_state,
+                amm_config: self.amm_config,
+                token_0_vault: self.token_0_vault,
+                token_1_vault: self.token_1_vault,
+                token_0_mint: self.token_0_mint,
+                observation_state: self.observation_state,
+                token_program: spl_token::id(),
+                candle_state: self.candle_state,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::BuybackAndBurn {
+                amount_in_requested: u64::MAX,
+                minimum_burn_amount: 1,
+            }
+            .data(),
+        }
+    }
+
+    /// Graduates the pool, the admin receives the vaults.
+    /// Needs a pool locked with `update_pool_status`
+    pub async fn pre_deploy_pair(&mut self) -> std::result::Result<u64, BanksClientError> {
+        let admin = booster_swap::admin::id();
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::PreDeployPair {
+                payer: admin,
+                create_pool_fee: booster_swap::create_pool_fee_receiver::id(),
+                authority: self.authority,
+                amm_config: self.amm_config,
+                pool_state: self.pool_state,
+                token_0_account: self.admin_token_0_account,
+                token_1_account: admin,
+                token_0_vault: self.token_0_vault,
+                token_1_vault: self.token_1_vault,
+                token_0_mint: self.token_0_mint,
+                metadata: self.metadata,
+                observation_state: self.observation_state,
+                price_feed: booster_swap::sol_price_feed::id(),
+                token_program: spl_token::id(),
+                system_program: system_program::ID,
+                token_metadata_program: mpl_token_metadata::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::PreDeployPair {}.data(),
+        };
+        self.process(&[instruction], &[]).await
+    }
+
+    /// Lets the admin, creator of the pool, rename token_0
+    /// `update_token_metadata` signed by `pool_creator`
+    pub fn update_token_metadata_ix(
+        &self,
+        pool_creator: Pubkey,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::UpdateTokenMetadata {
+                pool_creator,
+                pool_state: self.pool_state,
+                authority: self.authority,
+                token_0_mint: self.token_0_mint,
+                metadata: self.metadata,
+                token_metadata_program: mpl_token_metadata::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::UpdateTokenMetadata {
+                metadata: booster_swap::TokenMetadataParams {
+                    name: name.to_string(),
+                    symbol: symbol.to_string(),
+                    uri: uri.to_string(),
+                },
+            }
+            .data(),
+        }
+    }
+
+    /// Lets the admin, creator of the pool, update the token metadata
+    pub async fn update_token_metadata(
+        &mut self,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let instruction =
+            self.update_token_metadata_ix(booster_swap::admin::id(), name, symbol, uri);
+        self.process(&[instruction], &[]).await
+    }
+
+    fn candle_state_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[CANDLE_SEED.as_bytes(), self.pool_state.as_ref()],
+            &booster_swap::id(),
+        )
+        .0
+    }
+
+    /// `create_candle_state` of the pool paid by `payer`
+    pub fn create_candle_state_ix(&self, payer: Pubkey) -> Instruction {
+        Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::CreateCandleState {
+                payer,
+                pool_state: self.pool_state,
+                candle_state: self.candle_state_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::CreateCandleState {}.data(),
+        }
+    }
+
+    /// Lets the admin, creator of the pool, create its candle account,
+    /// the swap helpers pass it from then on
+    pub async fn create_candle_state(&mut self) -> std::result::Result<u64, BanksClientError> {
+        let instruction = self.create_candle_state_ix(booster_swap::admin::id());
+        let compute_units = self.process(&[instruction], &[]).await?;
+        self.candle_state = Some(self.candle_state_address());
+        Ok(compute_units)
+    }
+
+    /// The latest `count` candles of `resolution` returned by `get_candles`
+    pub async fn get_candles(
+        &mut self,
+        resolution: CandleResolution,
+        count: u8,
+    ) -> std::result::Result<Vec<booster_swap::instructions::CandleData>, BanksClientError> {
+        let instruction = Instruction {
+            program_id: booster_swap::id(),
+            accounts: booster_swap::accounts::GetCandles {
+                pool_state: self.pool_state,
+                candle_state: self.candle_state.unwrap(),
+            }
+            .to_account_metas(None),
+            data: booster_swap::instruction::GetCandles {
+                resolution: resolution as u8,
+                count,
+            }
+            .data(),
+        };
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let result = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        result.result.unwrap()?;
+        // the runtime drops the trailing zeros of the return data
+        let mut data = result.simulation_details.unwrap().return_data.unwrap().data;
+        data.resize(anchor_lang::solana_program::program::MAX_RETURN_DATA, 0);
+        Ok(AnchorDeserialize::deserialize(&mut data.as_slice()).unwrap())
+    }
+
+    /// The unix timestamp of the bank clock
+    pub async fn unix_timestamp(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u64
+    }
+
+    /// Moves the bank clock `seconds` forward
+    pub async fn warp_clock(&mut self, seconds: u64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn pool_state(&mut self) -> PoolState {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.pool_state)
+            .await
+            .unwrap()
+            .unwrap();
+        *bytemuck::from_bytes(&account.data[8..std::mem::size_of::<PoolState>() + 8])
+    }
+
+    pub async fn amm_config(&mut self) -> AmmConfig {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.amm_config)
+            .await
+            .unwrap()
+            .unwrap();
+        *bytemuck::from_bytes(&account.data[8..std::mem::size_of::<AmmConfig>() + 8])
+    }
+
+    pub async fn token_metadata(&mut self) -> Metadata {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.metadata)
+            .await
+            .unwrap()
+            .unwrap();
+        Metadata::from_bytes(&account.data).unwrap()
+    }
+}
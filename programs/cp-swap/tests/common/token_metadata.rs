@@ -0,0 +1,166 @@
+//! Native stand-in for the token metadata program, registered by `cargo test`.
+//! It implements the two instructions the program calls, `CreateMetadataAccountV3`
+//! and `UpdateMetadataAccountV2`, with the authority and mutability checks of Metaplex.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult, program::invoke_signed, program_option::COption,
+        program_pack::Pack, system_instruction,
+    },
+};
+use anchor_spl::{
+    metadata::mpl_token_metadata::{
+        accounts::Metadata,
+        errors::MplTokenMetadataError,
+        instructions::{
+            CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2InstructionArgs,
+        },
+        types::Key,
+    },
+    token::spl_token,
+};
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+/// Metaplex allocates the metadata for its longest strings so updates never resize it
+const MAX_METADATA_LEN: usize = 679;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    let (discriminator, mut args) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match *discriminator {
+        CREATE_METADATA_ACCOUNT_V3 => create_metadata_account(
+            program_id,
+            accounts,
+            AnchorDeserialize::deserialize(&mut args)?,
+        ),
+        UPDATE_METADATA_ACCOUNT_V2 => update_metadata_account(
+            program_id,
+            accounts,
+            AnchorDeserialize::deserialize(&mut args)?,
+        ),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn create_metadata_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateMetadataAccountV3InstructionArgs,
+) -> ProgramResult {
+    let [metadata, mint, mint_authority, payer, update_authority, system_program, ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let mint_state = spl_token::state::Mint::unpack(&mint.try_borrow_data()?)?;
+    if !mint_authority.is_signer || mint_state.mint_authority != COption::Some(*mint_authority.key)
+    {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (address, bump) = Metadata::find_pda(mint.key);
+    if address != *metadata.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let state = Metadata {
+        key: Key::MetadataV1,
+        update_authority: *update_authority.key,
+        mint: *mint.key,
+        name: args.data.name,
+        symbol: args.data.symbol,
+        uri: args.data.uri,
+        seller_fee_basis_points: args.data.seller_fee_basis_points,
+        creators: args.data.creators,
+        primary_sale_happened: false,
+        is_mutable: args.is_mutable,
+        edition_nonce: None,
+        token_standard: None,
+        collection: args.data.collection,
+        uses: args.data.uses,
+        collection_details: args.collection_details,
+        programmable_config: None,
+    };
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            metadata.key,
+            Rent::get()?.minimum_balance(MAX_METADATA_LEN),
+            MAX_METADATA_LEN as u64,
+            program_id,
+        ),
+        &[payer.clone(), metadata.clone(), system_program.clone()],
+        &[&[b"metadata", program_id.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+    write_metadata(metadata, &state)
+}
+
+fn update_metadata_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateMetadataAccountV2InstructionArgs,
+) -> ProgramResult {
+    let [metadata, update_authority, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if metadata.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut state = Metadata::from_bytes(&metadata.try_borrow_data()?)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if !update_authority.is_signer {
+        return Err(metadata_error(
+            MplTokenMetadataError::UpdateAuthorityIsNotSigner,
+        ));
+    }
+    if state.update_authority != *update_authority.key {
+        return Err(metadata_error(
+            MplTokenMetadataError::UpdateAuthorityIncorrect,
+        ));
+    }
+    if !state.is_mutable {
+        return Err(metadata_error(MplTokenMetadataError::DataIsImmutable));
+    }
+
+    if let Some(data) = args.data {
+        state.name = data.name;
+        state.symbol = data.symbol;
+        state.uri = data.uri;
+        state.seller_fee_basis_points = data.seller_fee_basis_points;
+        state.creators = data.creators;
+        state.collection = data.collection;
+        state.uses = data.uses;
+    }
+    if let Some(new_update_authority) = args.new_update_authority {
+        state.update_authority = new_update_authority;
+    }
+    // like Metaplex, a primary sale can not be undone
+    if let Some(primary_sale_happened) = args.primary_sale_happened {
+        state.primary_sale_happened |= primary_sale_happened;
+    }
+    if let Some(is_mutable) = args.is_mutable {
+        state.is_mutable = is_mutable;
+    }
+    write_metadata(metadata, &state)
+}
+
+/// The custom error Metaplex returns for `error`
+fn metadata_error(error: MplTokenMetadataError) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+fn write_metadata(metadata: &AccountInfo, state: &Metadata) -> ProgramResult {
+    let bytes = state.try_to_vec()?;
+    let mut data = metadata.try_borrow_mut_data()?;
+    if bytes.len() > data.len() {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    data[..bytes.len()].copy_from_slice(&bytes);
+    data[bytes.len()..].fill(0);
+    Ok(())
+}
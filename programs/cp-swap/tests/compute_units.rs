@@ -0,0 +1,165 @@
+//! Compute-unit regression harness. Runs each instruction bots budget for against the SBF build
+//! and fails when one consumes more than `TOLERANCE_PERCENT` over `compute_units_baseline.txt`,
+//! or when one has no baseline, printing the `name = units` line to add. Run it with:
+//!
+//! ```shell
+//! cargo test-sbf -p booster-swap --test compute_units -- --nocapture
+//! ```
+//!
+//! An intended change is recorded by rerunning it with `UPDATE_CU_BASELINE=1`.
+//! `cargo test` runs the same instructions through the native entrypoint, which does not meter them.
+
+mod common;
+
+use booster_swap::curve::TradeDirection;
+use common::*;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// How much an instruction may exceed its baseline before the run fails
+const TOLERANCE_PERCENT: u64 = 5;
+const BASELINE_FILE: &str = "tests/compute_units_baseline.txt";
+
+fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(BASELINE_FILE)
+}
+
+/// Reads the `name = units` lines of the baseline, `#` starts a comment
+fn read_baseline() -> BTreeMap<String, u64> {
+    let content = std::fs::read_to_string(baseline_path()).unwrap_or_default();
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, units) = line
+                .split_once('=')
+                .unwrap_or_else(|| panic!("invalid baseline line: {}", line));
+            (name.trim().to_string(), units.trim().parse().unwrap())
+        })
+        .collect()
+}
+
+fn write_baseline(measured: &[(&str, u64)]) {
+    let mut content = String::from(
+        "# Compute units of the SBF build, checked by tests/compute_units.rs.\n\
+         # Regenerate with `UPDATE_CU_BASELINE=1 cargo test-sbf -p booster-swap --test compute_units`\n",
+    );
+    for (name, units) in measured {
+        content.push_str(&format!("{} = {}\n", name, units));
+    }
+    std::fs::write(baseline_path(), content).unwrap();
+}
+
+fn check_baseline(measured: &[(&str, u64)]) {
+    if std::env::var("UPDATE_CU_BASELINE").is_ok() {
+        write_baseline(measured);
+        return;
+    }
+    let baseline = read_baseline();
+    let mut regressions = vec![];
+    let mut missing = vec![];
+    for (name, units) in measured {
+        match baseline.get(*name) {
+            Some(baseline_units) => {
+                println!(
+                    "{}: {} CU (baseline {}, {:+.1}%)",
+                    name,
+                    units,
+                    baseline_units,
+                    (*units as f64 / *baseline_units as f64 - 1.0) * 100.0
+                );
+                if units * 100 > baseline_units * (100 + TOLERANCE_PERCENT) {
+                    regressions.push(format!(
+                        "{}: {} CU, baseline {}",
+                        name, units, baseline_units
+                    ));
+                }
+            }
+            None => missing.push(format!("{} = {}", name, units)),
+        }
+    }
+    if !missing.is_empty() {
+        panic!(
+            "no baseline for these instructions, add them to {}:\n{}",
+            BASELINE_FILE,
+            missing.join("\n")
+        );
+    }
+    assert!(
+        regressions.is_empty(),
+        "compute units exceed the baseline by more than {}%:\n{}\n\
+         record an intended change with UPDATE_CU_BASELINE=1",
+        TOLERANCE_PERCENT,
+        regressions.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn compute_units_regression() {
+    // the SBF build has no native stand-in of the token metadata program
+    assert!(
+        !is_sbf() || token_metadata_program().is_some(),
+        "dump the token metadata program to {} to run the SBF build",
+        TOKEN_METADATA_PROGRAM_FIXTURE
+    );
+    let mut pool = setup_pool(1).await;
+    let mut measured = vec![("initialize", pool.initialize_compute_units)];
+
+    // the first swap of a wallet creates its user position, measure the swaps after it
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let units = pool
+        .swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL / 2, 0)
+        .await
+        .unwrap();
+    measured.push(("swap_base_input_buy", units));
+
+    let token_0_amount = pool.token_balance(pool.users[0].token_0_account).await;
+    let units = pool
+        .swap_base_input(0, TradeDirection::ZeroForOne, token_0_amount / 4, 0)
+        .await
+        .unwrap();
+    measured.push(("swap_base_input_sell", units));
+
+    let units = pool
+        .swap_base_output(
+            0,
+            TradeDirection::OneForZero,
+            LAMPORTS_PER_SOL,
+            token_0_amount / 8,
+        )
+        .await
+        .unwrap();
+    measured.push(("swap_base_output_buy", units));
+
+    let units = pool
+        .swap_base_output(
+            0,
+            TradeDirection::ZeroForOne,
+            token_0_amount,
+            LAMPORTS_PER_SOL / 10,
+        )
+        .await
+        .unwrap();
+    measured.push(("swap_base_output_sell", units));
+
+    let units = pool.collect_protocol_fee().await.unwrap();
+    measured.push(("collect_protocol_fee", units));
+
+    // fill the vault past MIN_AMOUNT_TO_DEPLOY and lock the pool to graduate it
+    pool.swap_base_input(0, TradeDirection::OneForZero, 90 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.update_pool_status(1).await.unwrap();
+    let units = pool.pre_deploy_pair().await.unwrap();
+    measured.push(("pre_deploy_pair", units));
+
+    if !is_sbf() {
+        println!("compute units are only metered by `cargo test-sbf`");
+        return;
+    }
+    check_baseline(&measured);
+}
@@ -0,0 +1,577 @@
+//! End-to-end lifecycle of a pool on a `ProgramTest` bank: `create_amm_config`, `create_mint`
+//! and `initialize` by the admin, buys and sells on the bonding curve, then `pre_deploy_pair`.
+//! Replaces the TypeScript suite's mainnet clone of the token metadata program, see `common`.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use booster_swap::{curve::TradeDirection, error::ErrorCode, states::*};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair};
+
+/// Metaplex pads the strings it stores with zeros
+fn trimmed(value: &str) -> &str {
+    value.trim_end_matches('\0')
+}
+
+#[tokio::test]
+async fn lifecycle() {
+    let mut pool = setup_pool(2).await;
+
+    let amm_config = pool.amm_config().await;
+    assert_eq!({ amm_config.index }, AMM_CONFIG_INDEX);
+    assert_eq!(
+        { amm_config.trade_from_one_to_zero_fee_rate },
+        TRADE_FEE_RATE
+    );
+    assert_eq!({ amm_config.protocol_fee_rate }, PROTOCOL_FEE_RATE);
+    assert_eq!({ amm_config.protocol_owner }, pool.fee_owner.pubkey());
+    assert_eq!({ amm_config.fund_owner }, pool.fee_owner.pubkey());
+
+    let metadata = pool.token_metadata().await;
+    assert_eq!(metadata.mint, pool.token_0_mint);
+    assert_eq!(metadata.update_authority, pool.authority);
+    assert_eq!(trimmed(&metadata.name), TOKEN_0_NAME);
+    assert_eq!(trimmed(&metadata.symbol), TOKEN_0_SYMBOL);
+    assert_eq!(trimmed(&metadata.uri), TOKEN_0_URI);
+    assert!(metadata.is_mutable);
+
+    let pool_state = pool.pool_state().await;
+    assert_eq!({ pool_state.amm_config }, pool.amm_config);
+    assert_eq!({ pool_state.token_0_mint }, pool.token_0_mint);
+    assert_eq!({ pool_state.token_0_vault }, pool.token_0_vault);
+    assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+    let token_0_vault_amount = pool.token_balance(pool.token_0_vault).await;
+    assert!(token_0_vault_amount > 0);
+
+    // buys move lamports into token_1_vault and token_0 out of token_0_vault
+    let user_lamports = pool.lamports(pool.users[0].pubkey()).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let amount_out = 1_000 * 10u64.pow(TOKEN_0_DECIMALS.into());
+    pool.swap_base_output(1, TradeDirection::OneForZero, LAMPORTS_PER_SOL, amount_out)
+        .await
+        .unwrap();
+    let bought = pool.token_balance(pool.users[0].token_0_account).await;
+    assert!(bought > 0);
+    assert_eq!(
+        pool.token_balance(pool.users[1].token_0_account).await,
+        amount_out
+    );
+    assert!(pool.lamports(pool.users[0].pubkey()).await <= user_lamports - LAMPORTS_PER_SOL);
+    assert!(pool.token_balance(pool.token_0_vault).await < token_0_vault_amount);
+
+    // selling half of it pays lamports back
+    let user_lamports = pool.lamports(pool.users[0].pubkey()).await;
+    pool.swap_base_input(0, TradeDirection::ZeroForOne, bought / 2, 1)
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.token_balance(pool.users[0].token_0_account).await,
+        bought - bought / 2
+    );
+    assert!(pool.lamports(pool.users[0].pubkey()).await > user_lamports);
+
+    pool.collect_protocol_fee().await.unwrap();
+    assert!(pool.lamports(pool.fee_owner.pubkey()).await > USER_LAMPORTS);
+
+    // the creator can still rename the token on the bonding curve
+    pool.update_token_metadata("Booster v2", "BST2", "https://example.com/v2.json")
+        .await
+        .unwrap();
+    let metadata = pool.token_metadata().await;
+    assert_eq!(trimmed(&metadata.name), "Booster v2");
+    assert_eq!(trimmed(&metadata.uri), "https://example.com/v2.json");
+
+    // fill token_1_vault past MIN_AMOUNT_TO_DEPLOY, lock the pool and graduate it
+    pool.swap_base_input(0, TradeDirection::OneForZero, 90 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.update_pool_status(1).await.unwrap();
+    let token_0_vault_amount = pool.token_balance(pool.token_0_vault).await;
+    let token_1_vault_lamports = pool.lamports(pool.token_1_vault).await;
+    assert!(token_1_vault_lamports >= MIN_AMOUNT_TO_DEPLOY);
+    let admin_lamports = pool.lamports(booster_swap::admin::id()).await;
+    let fee_receiver_lamports = pool
+        .lamports(booster_swap::create_pool_fee_receiver::id())
+        .await;
+    pool.pre_deploy_pair().await.unwrap();
+
+    assert_eq!(
+        pool.token_balance(pool.admin_token_0_account).await,
+        token_0_vault_amount
+    );
+    assert_eq!(
+        pool.lamports(booster_swap::admin::id()).await,
+        admin_lamports + token_1_vault_lamports
+    );
+    assert_eq!(pool.lamports(pool.token_0_vault).await, 0);
+    assert_eq!(pool.lamports(pool.token_1_vault).await, 0);
+    assert_eq!(pool.lamports(pool.observation_state).await, 0);
+    // the pool state is closed, its rent goes to the fee receiver with the others
+    assert_eq!(pool.lamports(pool.pool_state).await, 0);
+    assert!(
+        pool.lamports(booster_swap::create_pool_fee_receiver::id())
+            .await
+            > fee_receiver_lamports
+    );
+    assert!(!pool.token_metadata().await.is_mutable);
+
+    // nothing trades on, or renames, a graduated pool
+    assert!(pool
+        .swap_base_input(1, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .is_err());
+    assert!(pool
+        .update_token_metadata("Rug", "RUG", "https://example.com/rug.json")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn pre_deploy_pair_closes_each_vault_once() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 90 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.update_pool_status(1).await.unwrap();
+    let token_0_vault_rent = pool.lamports(pool.token_0_vault).await;
+    let closed_rent = token_0_vault_rent
+        + pool.lamports(pool.pool_state).await
+        + pool.lamports(pool.observation_state).await;
+    let fee_receiver_lamports = pool
+        .lamports(booster_swap::create_pool_fee_receiver::id())
+        .await;
+    pool.pre_deploy_pair().await.unwrap();
+
+    // token_0_vault is closed by the token program, token_1_vault was drained to the creator.
+    // Neither is closed a second time into the fee receiver
+    assert_eq!(pool.lamports(pool.token_0_vault).await, 0);
+    assert_eq!(pool.lamports(pool.token_1_vault).await, 0);
+    assert_eq!(
+        pool.lamports(booster_swap::create_pool_fee_receiver::id())
+            .await,
+        fee_receiver_lamports + closed_rent
+    );
+}
+
+#[tokio::test]
+async fn pre_deploy_pair_needs_locked_pool() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 90 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        custom_error(pool.pre_deploy_pair().await),
+        ErrorCode::NotApproved as u32 + ERROR_CODE_OFFSET
+    );
+}
+
+#[tokio::test]
+async fn initialize_needs_mint_creator() {
+    let mut pool = setup_mint(1).await;
+    assert_eq!(
+        custom_error(pool.initialize_by(0).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+}
+
+#[tokio::test]
+async fn update_token_metadata_by_pool_creator() {
+    let mut pool = setup_pool(1).await;
+    pool.update_token_metadata(
+        TOKEN_0_NAME,
+        TOKEN_0_SYMBOL,
+        "https://example.com/fixed.json",
+    )
+    .await
+    .unwrap();
+    let metadata = pool.token_metadata().await;
+    assert_eq!(trimmed(&metadata.name), TOKEN_0_NAME);
+    assert_eq!(trimmed(&metadata.uri), "https://example.com/fixed.json");
+    // the creator entry `initialize` checks survives the update
+    let creators = metadata.creators.unwrap();
+    assert_eq!(creators.len(), 1);
+    assert_eq!(creators[0].address, booster_swap::admin::id());
+    assert_eq!(creators[0].share, 100);
+
+    pool.update_token_metadata(
+        "Booster Renamed",
+        TOKEN_0_SYMBOL,
+        "https://example.com/fixed.json",
+    )
+    .await
+    .unwrap();
+    let metadata = pool.token_metadata().await;
+    assert_eq!(trimmed(&metadata.name), "Booster Renamed");
+    assert_eq!(trimmed(&metadata.symbol), TOKEN_0_SYMBOL);
+    assert_eq!(metadata.update_authority, pool.authority);
+    assert!(metadata.is_mutable);
+
+    // anyone else is rejected
+    let user = pool.users[0].pubkey();
+    let instruction =
+        pool.update_token_metadata_ix(user, "Rug", "RUG", "https://example.com/rug.json");
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&signer]).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+    assert_eq!(
+        trimmed(&pool.token_metadata().await.name),
+        "Booster Renamed"
+    );
+}
+
+#[tokio::test]
+async fn max_buy_counts_every_purchase() {
+    let mut pool = setup_pool(1).await;
+    // 1% of AVAILABLE_AMOUNT per wallet, the pool opened at 0 so the window never ends
+    pool.update_amm_config(11, 10_000).await.unwrap();
+    pool.update_amm_config(12, u64::MAX).await.unwrap();
+    let max_buy_amount = AVAILABLE_AMOUNT / 100 * 10u64.pow(TOKEN_0_DECIMALS.into());
+
+    pool.swap_base_output(
+        0,
+        TradeDirection::OneForZero,
+        10 * LAMPORTS_PER_SOL,
+        max_buy_amount,
+    )
+    .await
+    .unwrap();
+    // selling does not give the room back
+    pool.swap_base_input(0, TradeDirection::ZeroForOne, max_buy_amount / 2, 1)
+        .await
+        .unwrap();
+    let amount_out = 1_000 * 10u64.pow(TOKEN_0_DECIMALS.into());
+    assert_eq!(
+        custom_error(
+            pool.swap_base_output(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, amount_out)
+                .await
+        ),
+        ErrorCode::ExceededMaxBuy as u32 + ERROR_CODE_OFFSET
+    );
+
+    // the cap is lifted once the opening window is over. Another amount, as the bank drops
+    // a transaction identical to the rejected one under the same blockhash
+    pool.update_amm_config(12, 0).await.unwrap();
+    let token_0_amount = pool.token_balance(pool.users[0].token_0_account).await;
+    pool.swap_base_output(
+        0,
+        TradeDirection::OneForZero,
+        LAMPORTS_PER_SOL,
+        amount_out + 1,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        pool.token_balance(pool.users[0].token_0_account).await,
+        token_0_amount + amount_out + 1
+    );
+}
+
+#[tokio::test]
+async fn close_user_position_after_window() {
+    let mut pool = setup_pool(1).await;
+    // the pool opened at 0, the window never ends until its duration is set to 0
+    pool.update_amm_config(11, 10_000).await.unwrap();
+    pool.update_amm_config(12, u64::MAX).await.unwrap();
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL / 10, 0)
+        .await
+        .unwrap();
+    let user_position = pool.swap_accounts(&pool.users[0]).user_position;
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+
+    // the position backs the max buy while the window is open
+    let instruction = pool.close_user_position_ix(&pool.users[0]);
+    assert_eq!(
+        custom_error(pool.process(&[instruction.clone()], &[&signer]).await),
+        ErrorCode::NotApproved as u32 + ERROR_CODE_OFFSET
+    );
+
+    // only the owner closes it
+    let fee_owner = Keypair::from_bytes(&pool.fee_owner.keypair.to_bytes()).unwrap();
+    let mut other = pool.close_user_position_ix(&pool.fee_owner);
+    other.accounts[3].pubkey = user_position;
+    assert_eq!(
+        custom_error(pool.process(&[other], &[&fee_owner]).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+
+    pool.update_amm_config(12, 0).await.unwrap();
+    // the bank drops a transaction identical to the rejected one under the same blockhash
+    pool.context.get_new_latest_blockhash().await.unwrap();
+    let rent = pool.lamports(user_position).await;
+    let lamports = pool.lamports(pool.users[0].pubkey()).await;
+    pool.process(&[instruction], &[&signer]).await.unwrap();
+    assert_eq!(pool.lamports(user_position).await, 0);
+    assert_eq!(pool.lamports(pool.users[0].pubkey()).await, lamports + rent);
+
+    // the next trade creates it again
+    pool.swap_base_input(0, TradeDirection::OneForZero, 2 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    assert_eq!(pool.lamports(user_position).await, rent);
+
+    // once the pool graduated, its pool state is gone and the position can be closed
+    pool.swap_base_input(0, TradeDirection::OneForZero, 90 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.update_pool_status(1).await.unwrap();
+    pool.pre_deploy_pair().await.unwrap();
+    let instruction = pool.close_user_position_ix(&pool.users[0]);
+    pool.process(&[instruction], &[&signer]).await.unwrap();
+    assert_eq!(pool.lamports(user_position).await, 0);
+}
+
+#[tokio::test]
+async fn swaps_fail_past_deadline() {
+    let mut pool = setup_pool(1).await;
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    let now = pool.unix_timestamp().await;
+    let amount_out = 1_000 * 10u64.pow(TOKEN_0_DECIMALS.into());
+
+    let expired = [
+        pool.swap_base_input_ix(
+            &pool.users[0],
+            TradeDirection::OneForZero,
+            LAMPORTS_PER_SOL,
+            0,
+            Some(now - 1),
+        ),
+        pool.swap_base_output_ix(
+            &pool.users[0],
+            TradeDirection::OneForZero,
+            LAMPORTS_PER_SOL,
+            amount_out,
+            Some(now - 1),
+        ),
+    ];
+    for instruction in expired {
+        assert_eq!(
+            custom_error(pool.process(&[instruction], &[&signer]).await),
+            ErrorCode::DeadlineExceeded as u32 + ERROR_CODE_OFFSET
+        );
+    }
+
+    let valid = [
+        pool.swap_base_input_ix(
+            &pool.users[0],
+            TradeDirection::OneForZero,
+            LAMPORTS_PER_SOL,
+            0,
+            Some(now + 60),
+        ),
+        pool.swap_base_output_ix(
+            &pool.users[0],
+            TradeDirection::OneForZero,
+            LAMPORTS_PER_SOL,
+            amount_out,
+            Some(now + 60),
+        ),
+    ];
+    for instruction in valid {
+        pool.process(&[instruction], &[&signer]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn initialize_observation_num_limit() {
+    let mut pool = setup_mint(0).await;
+    let too_large = pool.initialize_ix(
+        booster_swap::admin::id(),
+        Some(OBSERVATION_NUM_CREATE_MAX + 1),
+    );
+    assert_eq!(
+        custom_error(pool.process(&[too_large], &[]).await),
+        ErrorCode::ObservationNumTooLarge as u32 + ERROR_CODE_OFFSET
+    );
+    let largest = pool.initialize_ix(booster_swap::admin::id(), Some(OBSERVATION_NUM_CREATE_MAX));
+    pool.process(&[largest], &[]).await.unwrap();
+    let observation_state = pool
+        .context
+        .banks_client
+        .get_account(pool.observation_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        observation_state.data.len(),
+        ObservationState::space(OBSERVATION_NUM_CREATE_MAX)
+    );
+}
+
+#[tokio::test]
+async fn collect_fund_fee_keeps_the_vault_rent_exempt() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 10 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let fund_fees_token_1 = { pool.pool_state().await.fund_fees_token_1 };
+    assert!(fund_fees_token_1 > 1);
+
+    // the fund fees go to the fund owner only
+    let other = pool.users[0].pubkey();
+    assert_eq!(
+        custom_error(pool.collect_fund_fee(other, 0, u64::MAX).await),
+        anchor_lang::error::ErrorCode::ConstraintAddress as u32
+    );
+
+    // leave the vault half of the fees above its rent exempt minimum
+    let rent_exempt_minimum = pool
+        .context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(0);
+    let mut vault = pool
+        .context
+        .banks_client
+        .get_account(pool.token_1_vault)
+        .await
+        .unwrap()
+        .unwrap();
+    vault.lamports = rent_exempt_minimum + fund_fees_token_1 / 2;
+    pool.context.set_account(&pool.token_1_vault, &vault.into());
+
+    let fee_owner = pool.fee_owner.pubkey();
+    let fee_owner_lamports = pool.lamports(fee_owner).await;
+    pool.collect_fund_fee(fee_owner, 0, u64::MAX).await.unwrap();
+    assert_eq!(pool.lamports(pool.token_1_vault).await, rent_exempt_minimum);
+    assert_eq!(
+        pool.lamports(fee_owner).await,
+        fee_owner_lamports + fund_fees_token_1 / 2
+    );
+    // only the amount paid is deducted from the accrued fees
+    assert_eq!(
+        { pool.pool_state().await.fund_fees_token_1 },
+        fund_fees_token_1 - fund_fees_token_1 / 2
+    );
+}
+
+#[tokio::test]
+async fn buyback_opted_in_by_pool_creator() {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 10 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let protocol_fees_token_1 = { pool.pool_state().await.protocol_fees_token_1 };
+    assert!(protocol_fees_token_1 > 0);
+
+    // no buyback until the creator opts in, and only the creator can
+    let fee_owner = Keypair::from_bytes(&pool.fee_owner.keypair.to_bytes()).unwrap();
+    let instruction = pool.buyback_and_burn_ix(pool.fee_owner.pubkey());
+    assert_eq!(
+        custom_error(pool.process(&[instruction.clone()], &[&fee_owner]).await),
+        ErrorCode::NotApproved as u32 + ERROR_CODE_OFFSET
+    );
+    let trader = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    let opt_in = pool.set_buyback_ix(pool.users[0].pubkey(), true);
+    assert_eq!(
+        custom_error(pool.process(&[opt_in], &[&trader]).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+    pool.set_buyback(true).await.unwrap();
+    assert_eq!({ pool.pool_state().await.buyback_enabled }, 1);
+
+    let buyback = pool.buyback_and_burn_ix(pool.users[0].pubkey());
+    assert_eq!(
+        custom_error(pool.process(&[buyback], &[&trader]).await),
+        ErrorCode::InvalidOwner as u32 + ERROR_CODE_OFFSET
+    );
+
+    // the protocol owner may run it for an opted in pool. The bank drops a transaction
+    // identical to the rejected one under the same blockhash
+    pool.context.get_new_latest_blockhash().await.unwrap();
+    let token_0_vault_amount = pool.token_balance(pool.token_0_vault).await;
+    let before = pool.pool_state().await;
+    pool.process(&[instruction], &[&fee_owner]).await.unwrap();
+    let after = pool.pool_state().await;
+    assert_eq!({ after.protocol_fees_token_1 }, 0);
+    assert!(pool.token_balance(pool.token_0_vault).await < token_0_vault_amount);
+
+    // the buyback is recorded like a buy that raises the price
+    assert_eq!({ after.trade_count }, { before.trade_count } + 1);
+    assert_eq!(
+        { after.buy_volume_token_1 },
+        { before.buy_volume_token_1 } + protocol_fees_token_1
+    );
+    assert_eq!({ after.unique_buyer_count }, { before.unique_buyer_count });
+    assert!({ after.ath_price_x32 } > { before.ath_price_x32 });
+
+    // so may the creator, once the interval passed
+    pool.swap_base_input(0, TradeDirection::OneForZero, 2 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    pool.warp_clock(BUYBACK_MIN_INTERVAL).await;
+    let instruction = pool.buyback_and_burn_ix(booster_swap::admin::id());
+    pool.process(&[instruction], &[]).await.unwrap();
+    assert_eq!({ pool.pool_state().await.protocol_fees_token_1 }, 0);
+}
+
+#[tokio::test]
+async fn migrate_version_0_pool_state() {
+    // pools created before versioning: `open_time` followed by a zeroed `[u64; 32]` padding,
+    // in an account sized by the `PoolState::LEN` of that layout
+    const VERSION_0_LEN: usize = 8 + 8 + 9 * 32 + 1 * 5 + 8 * 6 + 8 * 32;
+    const VERSION_0_FIELDS_END: usize = 8 + 309;
+
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(pool.pool_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut data = vec![0u8; VERSION_0_LEN];
+    data[..VERSION_0_FIELDS_END].copy_from_slice(&account.data[..VERSION_0_FIELDS_END]);
+    account.data = data;
+    let rent: Rent = pool.context.banks_client.get_sysvar().await.unwrap();
+    account.lamports = rent.minimum_balance(VERSION_0_LEN);
+    pool.context.set_account(&pool.pool_state, &account.into());
+
+    let before = pool.pool_state().await;
+    assert_eq!({ before.version }, 0);
+    assert_eq!({ before.trade_count }, 0);
+
+    pool.migrate_pool_state().await.unwrap();
+    let account = pool
+        .context
+        .banks_client
+        .get_account(pool.pool_state)
+        .await
+        .unwrap()
+        .unwrap();
+    // the older layout is larger, the account keeps its size
+    assert_eq!(account.data.len(), VERSION_0_LEN);
+    assert!(account.data[PoolState::LEN..].iter().all(|byte| *byte == 0));
+    let after = pool.pool_state().await;
+    assert_eq!({ after.version }, POOL_STATE_VERSION);
+    assert_eq!({ after.amm_config }, pool.amm_config);
+    assert_eq!({ after.pool_creator }, booster_swap::admin::id());
+    assert_eq!({ after.token_0_vault }, pool.token_0_vault);
+    assert_eq!({ after.token_1_vault }, pool.token_1_vault);
+    assert_eq!({ after.token_0_mint }, pool.token_0_mint);
+    assert_eq!({ after.observation_key }, pool.observation_state);
+    assert_eq!({ after.status }, { before.status });
+    assert_eq!({ after.lp_supply }, { before.lp_supply });
+    assert_eq!({ after.protocol_fees_token_1 }, { before.protocol_fees_token_1 });
+    assert!({ after.protocol_fees_token_1 } > 0);
+    assert_eq!({ after.fund_fees_token_1 }, { before.fund_fees_token_1 });
+    assert_eq!({ after.open_time }, { before.open_time });
+
+    // the migrated pool trades and records its statistics
+    pool.swap_base_input(0, TradeDirection::OneForZero, 2 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let traded = pool.pool_state().await;
+    assert_eq!({ traded.trade_count }, 1);
+    assert_eq!({ traded.buy_volume_token_1 }, 2 * LAMPORTS_PER_SOL);
+    assert!({ traded.ath_price_x32 } > 0);
+}
@@ -0,0 +1,132 @@
+//! `swap_batch` in atomic and skip mode: a skipped leg must leave its pool as it found it.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use booster_swap::{
+    curve::TradeDirection, error::ErrorCode, instructions::SwapLeg, states::UserPosition,
+};
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair};
+
+fn buy(amount_in: u64, minimum_amount_out: u64) -> SwapLeg {
+    SwapLeg {
+        trade_direction: TradeDirection::OneForZero as u8,
+        amount_in,
+        minimum_amount_out,
+    }
+}
+
+/// Runs `swap_batch` of `legs` for the first user
+async fn swap_batch(
+    pool: &mut PoolFixture,
+    legs: &[SwapLeg],
+    skip_failed_legs: bool,
+) -> std::result::Result<u64, BanksClientError> {
+    let instruction = pool.swap_batch_ix(&pool.users[0], legs, skip_failed_legs);
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    pool.process(&[instruction], &[&signer]).await
+}
+
+async fn user_position(pool: &mut PoolFixture) -> Option<UserPosition> {
+    let address = pool.swap_accounts(&pool.users[0]).user_position;
+    let account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()?;
+    Some(UserPosition::try_deserialize(&mut account.data.as_slice()).unwrap())
+}
+
+#[tokio::test]
+async fn atomic_batch_fails_with_any_leg() {
+    let mut pool = setup_pool(1).await;
+    let vault_lamports = pool.lamports(pool.token_1_vault).await;
+    let legs = [buy(LAMPORTS_PER_SOL, 0), buy(LAMPORTS_PER_SOL, u64::MAX)];
+    assert_eq!(
+        custom_error(swap_batch(&mut pool, &legs, false).await),
+        ErrorCode::ExceededSlippage as u32 + ERROR_CODE_OFFSET
+    );
+    assert_eq!(pool.token_balance(pool.users[0].token_0_account).await, 0);
+    assert_eq!(pool.lamports(pool.token_1_vault).await, vault_lamports);
+    assert!(user_position(&mut pool).await.is_none());
+}
+
+#[tokio::test]
+async fn skipped_leg_leaves_the_pool_untouched() {
+    // the same single buy on an identical pool is the reference
+    let mut reference = setup_pool(1).await;
+    reference
+        .swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+
+    let mut pool = setup_pool(1).await;
+    let legs = [
+        buy(LAMPORTS_PER_SOL, u64::MAX),
+        buy(LAMPORTS_PER_SOL, 0),
+        buy(LAMPORTS_PER_SOL, u64::MAX),
+    ];
+    swap_batch(&mut pool, &legs, true).await.unwrap();
+
+    assert_eq!(
+        pool.token_balance(pool.users[0].token_0_account).await,
+        reference
+            .token_balance(reference.users[0].token_0_account)
+            .await
+    );
+    assert_eq!(
+        pool.lamports(pool.token_1_vault).await,
+        reference.lamports(reference.token_1_vault).await
+    );
+    let pool_state = pool.pool_state().await;
+    let reference_state = reference.pool_state().await;
+    assert_eq!({ pool_state.protocol_fees_token_1 }, {
+        reference_state.protocol_fees_token_1
+    });
+    assert_eq!({ pool_state.trade_count }, { reference_state.trade_count });
+    let position = user_position(&mut pool).await.unwrap();
+    assert_eq!(
+        position.token_0_bought,
+        user_position(&mut reference).await.unwrap().token_0_bought
+    );
+}
+
+#[tokio::test]
+async fn skipped_first_leg_leaves_an_empty_position() {
+    let mut pool = setup_pool(1).await;
+    swap_batch(&mut pool, &[buy(LAMPORTS_PER_SOL, u64::MAX)], true)
+        .await
+        .unwrap();
+    assert_eq!(pool.token_balance(pool.users[0].token_0_account).await, 0);
+    let position = user_position(&mut pool).await.unwrap();
+    assert_eq!(position.token_0_bought, 0);
+    assert_eq!(position.owner, Pubkey::default());
+
+    // the position is usable by the next swap
+    pool.swap_base_input(0, TradeDirection::OneForZero, LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let position = user_position(&mut pool).await.unwrap();
+    assert_eq!(position.owner, pool.users[0].pubkey());
+    assert_eq!(
+        position.token_0_bought,
+        pool.token_balance(pool.users[0].token_0_account).await
+    );
+}
+
+#[tokio::test]
+async fn batch_needs_the_accounts_of_every_leg() {
+    let mut pool = setup_pool(1).await;
+    let legs = [buy(LAMPORTS_PER_SOL, 0), buy(LAMPORTS_PER_SOL, 0)];
+    let mut instruction = pool.swap_batch_ix(&pool.users[0], &legs, true);
+    instruction.accounts.pop();
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&signer]).await),
+        ErrorCode::InvalidInput as u32 + ERROR_CODE_OFFSET
+    );
+    assert_eq!(pool.token_balance(pool.users[0].token_0_account).await, 0);
+}
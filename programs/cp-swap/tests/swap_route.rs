@@ -0,0 +1,99 @@
+//! `swap_route` from the token_0 of one pool to the token_0 of another through SOL.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use booster_swap::{curve::TradeDirection, error::ErrorCode};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair};
+
+/// A pool the first user bought token_0 on, and a second pool with an empty token_0 account of the user
+async fn setup_route() -> (PoolFixture, PoolKeys, Pubkey) {
+    let mut pool = setup_pool(1).await;
+    pool.swap_base_input(0, TradeDirection::OneForZero, 10 * LAMPORTS_PER_SOL, 0)
+        .await
+        .unwrap();
+    let buy = pool.launch_pool().await;
+    let buy_token_0_account = pool.create_token_0_account(0, &buy).await;
+    (pool, buy, buy_token_0_account)
+}
+
+#[tokio::test]
+async fn route_spends_the_proceeds_on_the_second_pool() {
+    let (mut pool, buy, buy_token_0_account) = setup_route().await;
+    let user = pool.users[0].pubkey();
+    let sell_token_0_amount = pool.token_balance(pool.users[0].token_0_account).await;
+    let amount_in = sell_token_0_amount / 2;
+    let user_lamports = pool.lamports(user).await;
+    let buy_vault_token_0_amount = pool.token_balance(buy.token_0_vault).await;
+
+    let instruction = pool.swap_route_ix(&pool.users[0], &buy, buy_token_0_account, amount_in, 1);
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    pool.process(&[instruction], &[&signer]).await.unwrap();
+
+    assert_eq!(
+        pool.token_balance(pool.users[0].token_0_account).await,
+        sell_token_0_amount - amount_in
+    );
+    let bought = pool.token_balance(buy_token_0_account).await;
+    assert!(bought > 0);
+    assert_eq!(
+        pool.token_balance(buy.token_0_vault).await,
+        buy_vault_token_0_amount - bought
+    );
+    // everything received from the sale is spent, but the rent of the new position on the
+    // second pool and less than a lamport per fee rounding
+    let buy_position = pool
+        .swap_accounts_on(&buy, &pool.users[0], buy_token_0_account)
+        .user_position;
+    let position_rent = pool.lamports(buy_position).await;
+    let dust = (pool.lamports(user).await + position_rent)
+        .checked_sub(user_lamports)
+        .unwrap();
+    assert!(dust <= 1, "{} lamports left", dust);
+}
+
+#[tokio::test]
+async fn route_checks_slippage_on_the_final_amount() {
+    let (mut pool, buy, buy_token_0_account) = setup_route().await;
+    let sell_token_0_amount = pool.token_balance(pool.users[0].token_0_account).await;
+    let user_lamports = pool.lamports(pool.users[0].pubkey()).await;
+
+    let instruction = pool.swap_route_ix(
+        &pool.users[0],
+        &buy,
+        buy_token_0_account,
+        sell_token_0_amount / 2,
+        u64::MAX,
+    );
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&signer]).await),
+        ErrorCode::ExceededSlippage as u32 + ERROR_CODE_OFFSET
+    );
+    // the sale on the first pool is reverted with the buy
+    assert_eq!(
+        pool.token_balance(pool.users[0].token_0_account).await,
+        sell_token_0_amount
+    );
+    assert_eq!(pool.token_balance(buy_token_0_account).await, 0);
+    assert_eq!(pool.lamports(pool.users[0].pubkey()).await, user_lamports);
+}
+
+#[tokio::test]
+async fn route_needs_two_pools() {
+    let (mut pool, _, _) = setup_route().await;
+    let sell = pool.keys();
+    let instruction = pool.swap_route_ix(
+        &pool.users[0],
+        &sell,
+        pool.users[0].token_0_account,
+        1_000,
+        0,
+    );
+    let signer = Keypair::from_bytes(&pool.users[0].keypair.to_bytes()).unwrap();
+    assert_eq!(
+        custom_error(pool.process(&[instruction], &[&signer]).await),
+        ErrorCode::InvalidInput as u32 + ERROR_CODE_OFFSET
+    );
+}
@@ -1,9 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program, BN } from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
 import { BoosterSwap } from "../target/types/booster_swap";
-import { getMintAuthAddress, create_mint, mint_tokens } from "./utils";
+import { create_mint } from "./utils";
 import { expect } from "chai";
-import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
 
 describe("initialize test", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -21,42 +21,17 @@ describe("initialize test", () => {
     uri: "https://5vfxc4tr6xoy23qefqbj4qx2adzkzapneebanhcalf7myvn5gzja.arweave.net/7UtxcnH13Y1uBCwCnkL6APKsge0hAgacQFl-zFW9NlI",
     decimals: 9,
   };
-  const [mintAuth] = getMintAuthAddress(
-    program.programId,
-  );
+  const mint = Keypair.generate();
 
   it("create mint", async () => {
     const result = await create_mint(
       program,
       owner,
-      mintAuth,
+      mint,
       metadata,
       confirmOptions,
     );
     expect(result).to.be.not.null;
   });
 
-  it("mint tokens", async () => {
-    const [mintAuth] = getMintAuthAddress(
-      program.programId,
-    );
-
-    const quantity = new BN(1000000000);
-    const { txHash, destination } = await mint_tokens(
-      program,
-      owner,
-      mintAuth,
-      quantity,
-      confirmOptions,
-    );
-    expect(txHash).to.be.not.null;
-    const vault = await getAccount(
-      program.provider.connection,
-      destination,
-      "processed",
-      TOKEN_PROGRAM_ID
-    );
-    console.log(vault.amount.toString());
-    expect(vault.amount.toString()).to.be.eq(quantity.toString());
-  });
 });
@@ -3,9 +3,9 @@ import { Program, BN } from "@coral-xyz/anchor";
 import { BoosterSwap } from "../target/types/booster_swap";
 
 import { getAccount } from "@solana/spl-token";
-import { setupInitializeTest, initialize, TOKEN_TOTAL_SUPPLY, NATIVE_MINT, getMintAuthAddress } from "./utils";
+import { setupInitializeTest, initialize, TOKEN_TOTAL_SUPPLY, NATIVE_MINT } from "./utils";
 import { expect } from "chai";
-import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
 
 describe("initialize test", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -25,13 +25,13 @@ describe("initialize test", () => {
   };
 
   it("create pool", async () => {
-    const [mintAuth] = getMintAuthAddress(program.programId);
+    const mint = Keypair.generate();
 
     const { configAddress, token0 } =
       await setupInitializeTest(
         program,
         owner,
-        mintAuth,
+        mint,
         metadata,
         {
           config_index: 0,
@@ -1,9 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { BoosterSwap } from "../target/types/booster_swap";
-import { LAMPORTS_PER_SOL_DECIMAL, TradeDirection, getMintAuthAddress, getObservation, logPairBalance, setupSwapTest, swap_base_input, swap_base_output, toBigIntQuantity } from "./utils";
+import { LAMPORTS_PER_SOL_DECIMAL, TradeDirection, getObservation, logPairBalance, setupSwapTest, swap_base_input, swap_base_output, toBigIntQuantity } from "./utils";
 import { assert, expect } from "chai";
 import { getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
 
 describe("swap test", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -20,14 +21,14 @@ describe("swap test", () => {
     uri: "https://5vfxc4tr6xoy23qefqbj4qx2adzkzapneebanhcalf7myvn5gzja.arweave.net/7UtxcnH13Y1uBCwCnkL6APKsge0hAgacQFl-zFW9NlI",
     decimals: 9,
   };
-  const [mintAuth] = getMintAuthAddress(program.programId);
+  const mint = Keypair.generate();
   let configAddress, poolAddress, poolState;
 
   before(async () => {
     const resp = await setupSwapTest(
       program,
       owner,
-      mintAuth,
+      mint,
       metadata,
       {
         config_index: 0,
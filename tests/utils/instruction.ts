@@ -21,18 +21,18 @@ import {
   getPoolVaultAddress,
   createTokenMintAndAssociatedTokenAccount,
   getOracleAccountAddress,
+  getUserPositionAddress,
   getAmmConfigAddress,
   TOKEN_METADATA_PROGRAM_ID,
   getMintMetadataAddress,
   NATIVE_MINT,
 } from "./index";
 import { sendAndConfirmIx } from "./tx";
-import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 
 export async function setupInitializeTest(
   program: Program<BoosterSwap>,
   owner: Signer,
-  mintAuth: PublicKey,
+  mint: Keypair,
   mintMetadata: MintMetadata,
   config: {
     config_index: number;
@@ -47,7 +47,7 @@ export async function setupInitializeTest(
   const token0Mint = await create_mint(
     program,
     owner,
-    mintAuth,
+    mint,
     mintMetadata,
     confirmOptions,
   );
@@ -73,7 +73,7 @@ export async function setupInitializeTest(
 export async function setupSwapTest(
   program: Program<BoosterSwap>,
   owner: Signer,
-  mintAuth: PublicKey,
+  mint: Keypair,
   mintMetadata: MintMetadata,
   config: {
     config_index: number;
@@ -85,7 +85,7 @@ export async function setupSwapTest(
   },
   confirmOptions?: ConfirmOptions
 ) {
-  const { configAddress, token0 } = await setupInitializeTest(program, owner, mintAuth, mintMetadata, config, confirmOptions);
+  const { configAddress, token0 } = await setupInitializeTest(program, owner, mint, mintMetadata, config, confirmOptions);
 
   const { poolAddress, poolState } = await initialize(
     program,
@@ -147,20 +147,22 @@ export type MintMetadata = {
 export async function create_mint(
   program: Program<BoosterSwap>,
   creator: Signer,
-  mintAuth: PublicKey,
+  mint: Keypair,
   mintMetadata: MintMetadata,
   confirmOptions?: ConfirmOptions,
 ) {
-  if (await accountExist(program.provider.connection, mintAuth)) {
-    return mintAuth;
+  if (await accountExist(program.provider.connection, mint.publicKey)) {
+    return mint.publicKey;
   }
-  const [metadataAddr] = getMintMetadataAddress(mintAuth);
+  const [metadataAddr] = getMintMetadataAddress(mint.publicKey);
+  const [authority] = getAuthAddress(program.programId);
 
   const ix = await program.methods
     .createMint(mintMetadata)
     .accounts({
       metadata: metadataAddr,
-      mint: mintAuth,
+      mint: mint.publicKey,
+      authority,
       creator: creator.publicKey,
       tokenProgram: TOKEN_PROGRAM_ID,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
@@ -168,39 +170,9 @@ export async function create_mint(
       rent: SYSVAR_RENT_PUBKEY,
     })
     .instruction();
-  const txHash = await sendAndConfirmIx(program.provider.connection, [ix], [creator], undefined, confirmOptions);
+  const txHash = await sendAndConfirmIx(program.provider.connection, [ix], [creator, mint], undefined, confirmOptions);
   console.log("create mint tx: ", txHash);
-  return mintAuth;
-}
-
-export async function mint_tokens(
-  program: Program<BoosterSwap>,
-  creator: Signer,
-  mint: PublicKey,
-  quantity: BN,
-  confirmOptions?: ConfirmOptions,
-) {
-
-  const destination = getAssociatedTokenAddressSync(
-    mint,
-    creator.publicKey,
-  );
-
-  const ix = await program.methods
-    .mintTokens(quantity)
-    .accounts({
-      mint,
-      destination: destination,
-      payer: creator.publicKey,
-      rent: SYSVAR_RENT_PUBKEY,
-      systemProgram: SystemProgram.programId,
-      tokenProgram: TOKEN_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
-    })
-    .instruction();
-  const txHash = await sendAndConfirmIx(program.provider.connection, [ix], [creator], undefined, confirmOptions);
-  console.log("mint tokens tx: ", txHash);
-  return { txHash, destination };
+  return mint.publicKey;
 }
 
 export async function initialize(
@@ -238,15 +210,17 @@ export async function initialize(
     poolAddress,
     program.programId
   );
+  const [metadataAddr] = getMintMetadataAddress(token0);
 
   const ix = await program.methods
-    .initialize(new BN(0))
+    .initialize(new BN(0), null, null)
     .accounts({
       creator: creator.publicKey,
       ammConfig: configAddress,
       authority: authority,
       poolState: poolAddress,
       token0Mint: token0,
+      metadata: metadataAddr,
       // token1Mint: token1,
       token0Vault: vault0,
       token1Vault: vault1,
@@ -256,6 +230,7 @@ export async function initialize(
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: SYSVAR_RENT_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
     })
     .instruction();
   const txHash = await sendAndConfirmIx(program.provider.connection, [ix], [creator], undefined, confirmOptions);
@@ -308,12 +283,22 @@ export async function swap_base_input(
     poolAddress,
     program.programId
   );
+  const [userPosition] = getUserPositionAddress(
+    poolAddress,
+    owner.publicKey,
+    program.programId
+  );
+  // a pool with candles requires its candle account
+  const { candleKey } = await program.account.poolState.fetch(poolAddress);
+  const candleState = candleKey.equals(PublicKey.default) ? null : candleKey;
 
   const ix = await program.methods
     .swapBaseInput(
       tradeDirection,
       amountIn,
-      minimumAmountOut
+      minimumAmountOut,
+      null,
+      null
     )
     .accounts({
       payer: owner.publicKey,
@@ -327,6 +312,8 @@ export async function swap_base_input(
       token1Vault: vault1,
       token0Mint: token0,
       observationState: observationAddress,
+      userPosition,
+      candleState,
       tokenProgram: TOKEN_PROGRAM_ID,
     })
     .instruction();
@@ -374,12 +361,21 @@ export async function swap_base_output(
     poolAddress,
     program.programId
   );
+  const [userPosition] = getUserPositionAddress(
+    poolAddress,
+    owner.publicKey,
+    program.programId
+  );
+  // a pool with candles requires its candle account
+  const { candleKey } = await program.account.poolState.fetch(poolAddress);
+  const candleState = candleKey.equals(PublicKey.default) ? null : candleKey;
 
   const ix = await program.methods
     .swapBaseOutput(
       tradeDirection,
       maximumAmountIn,
       amountOut,
+      null,
     )
     .accounts({
       payer: owner.publicKey,
@@ -393,6 +389,8 @@ export async function swap_base_output(
       token1Vault: vault1,
       token0Mint: token0,
       observationState: observationAddress,
+      userPosition,
+      candleState,
       tokenProgram: TOKEN_PROGRAM_ID,
     })
     .instruction();
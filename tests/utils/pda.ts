@@ -11,9 +11,6 @@ export const POOL_VAULT_SEED = Buffer.from(
 export const POOL_AUTH_SEED = Buffer.from(
   anchor.utils.bytes.utf8.encode("vault_auth_seed")
 );
-export const CREATE_MINT_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("create_mint")
-);
 export const METADATA_SEED = Buffer.from(
   anchor.utils.bytes.utf8.encode("metadata")
 );
@@ -33,6 +30,26 @@ export const ORACLE_SEED = Buffer.from(
   anchor.utils.bytes.utf8.encode("observation")
 );
 
+export const USER_POSITION_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("user_position")
+);
+
+export const CANDLE_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("candle")
+);
+
+export const FEE_SPLIT_CONFIG_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("fee_split_config")
+);
+
+export const VESTING_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("vesting")
+);
+
+export const VESTING_VAULT_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("vesting_vault")
+);
+
 export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
 );
@@ -76,18 +93,6 @@ export function getAmmConfigAddress(
   return [address, bump];
 }
 
-export function getMintAuthAddress(
-  programId: PublicKey
-): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [
-      CREATE_MINT_SEED,
-    ],
-    programId
-  );
-  return [address, bump];
-}
-
 export function getMintMetadataAddress(
   mint: PublicKey,
 ): [PublicKey, number] {
@@ -169,3 +174,60 @@ export function getOracleAccountAddress(
   );
   return [address, bump];
 }
+
+export function getUserPositionAddress(
+  pool: PublicKey,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [USER_POSITION_SEED, pool.toBuffer(), user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getCandleAddress(
+  pool: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [CANDLE_SEED, pool.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getFeeSplitConfigAddress(
+  ammConfig: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [FEE_SPLIT_CONFIG_SEED, ammConfig.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getVestingAddress(
+  pool: PublicKey,
+  beneficiary: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [VESTING_SEED, pool.toBuffer(), beneficiary.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getVestingVaultAddress(
+  vesting: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [VESTING_VAULT_SEED, vesting.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}